@@ -0,0 +1,32 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+//! Plugin launcher used by e2e tests, it reports a preset launches count, knows a placeholder plugin code & cannot launch plugins
+
+pub use mock_launcher::{MockLauncherRef};
+
+#[openbrush::contract]
+mod mock_launcher {
+  use openbrush::traits::Storage;
+  use shared::traits::plugin_launcher::*;
+
+  #[ink(storage)]
+  #[derive(Default, Storage)]
+  pub struct MockLauncher {
+    #[storage_field]
+    launcher: plugin_launcher::Data,
+  }
+
+  impl plugin_launcher::Instantiator for MockLauncher {}
+  impl PluginLauncher for MockLauncher {}
+
+  impl MockLauncher {
+    #[ink(constructor)]
+    pub fn new(motherspace_id: AccountId, launches_count: u32) -> Self {
+      let mut one = Self::default();
+      plugin_launcher::PluginLauncher::_init(&mut one, motherspace_id, Hash::from([0x01; 32]));
+      one.launcher.launches_count.set(&launches_count);
+
+      one
+    }
+  }
+}
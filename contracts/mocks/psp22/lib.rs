@@ -0,0 +1,45 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+//! PSP22 token used by e2e tests of token subscriptions, transfers can be switched off to simulate a failing token
+
+pub use mock_psp22::{MockPsp22Ref};
+
+#[openbrush::implementation(PSP22)]
+#[openbrush::contract]
+mod mock_psp22 {
+  use openbrush::traits::{Storage, String};
+
+  #[ink(storage)]
+  #[derive(Default, Storage)]
+  pub struct MockPsp22 {
+    #[storage_field]
+    psp22: psp22::Data,
+
+    transfers_disabled: bool,
+  }
+
+  #[overrider(psp22::Internal)]
+  fn _before_token_transfer(&mut self, _from: Option<&AccountId>, _to: Option<&AccountId>, _amount: &Balance) -> Result<(), PSP22Error> {
+    if self.transfers_disabled {
+      return Err(PSP22Error::Custom(String::from("Transfers are disabled")));
+    }
+
+    Ok(())
+  }
+
+  impl MockPsp22 {
+    /// Mint `total_supply` to the deployer
+    #[ink(constructor)]
+    pub fn new(total_supply: Balance) -> Self {
+      let mut instance = Self::default();
+      psp22::Internal::_mint_to(&mut instance, Self::env().caller(), total_supply).expect("Cannot mint total supply");
+
+      instance
+    }
+
+    #[ink(message)]
+    pub fn set_transfers_disabled(&mut self, disabled: bool) {
+      self.transfers_disabled = disabled;
+    }
+  }
+}
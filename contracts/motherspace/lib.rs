@@ -1,5 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use motherspace::{MotherSpaceRef};
+
 #[openbrush::implementation(Ownable, Upgradeable)]
 #[openbrush::contract]
 mod motherspace {
@@ -7,17 +9,19 @@ mod motherspace {
   use ink::env::{
     DefaultEnvironment,
     call::{build_call, build_create, ExecutionInput, Selector},
+    hash::Blake2x256,
   };
   use ink::storage::{Lazy, Mapping};
   use ink::prelude::{format, vec::Vec, string::String};
   use ink::ToAccountId;
   use shared::ensure;
   use shared::traits::codehash::*;
+  use shared::traits::migratable::*;
+  use shared::traits::motherspace::*;
+  use shared::traits::plugin_launcher::LauncherError;
   use shared::traits::space_profile::*;
   use space::SpaceRef;
 
-  type MotherSpaceResult<T> = core::result::Result<T, MotherSpaceError>;
-
   type Nonce = u32;
   // index
   type Version = u32;
@@ -25,23 +29,13 @@ mod motherspace {
   type PluginIndex = u32;
   type PluginId = [u8; 4];
 
-  #[derive(Debug, scale::Decode, scale::Encode)]
-  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-  pub enum MotherSpaceError {
-    Custom(String),
-    OwnableError(OwnableError),
-    UnAuthorized,
-    SpaceNotFound,
-    PluginNotFound,
-    PluginLaunchFailed,
-    PluginIdExisted,
-  }
+  /// Bump this whenever a migration step is added to `migrate`
+  const LATEST_STORAGE_VERSION: StorageVersion = 1;
 
-  impl From<OwnableError> for MotherSpaceError {
-    fn from(error: OwnableError) -> Self {
-      MotherSpaceError::OwnableError(error)
-    }
-  }
+  const MAX_PLUGIN_CODES_QUERY: usize = 20;
+  const MAX_SPACES_MEMBERSHIP_QUERY: usize = 50;
+  const MAX_PLUGIN_STATS_QUERY: u32 = 20;
+  const DEFAULT_RESERVED_NAMES: [&str; 3] = ["inspace", "motherspace", "admin"];
 
   #[derive(Clone, Debug, scale::Decode, scale::Encode)]
   #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -54,6 +48,74 @@ mod motherspace {
   }
 
   type SpacesPage = Pagination<(SpaceId, Hash)>;
+  type MembersPage = Pagination<AccountId>;
+
+  #[derive(Clone, Debug, PartialEq, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub enum PluginInstallStatus {
+    Installed(AccountId),
+    LauncherNotFound,
+    // launcher failed the liveness probe
+    LauncherUnreachable,
+    // launcher trapped or returned an error on launch
+    LaunchFailed,
+  }
+
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct PluginEcosystemStats {
+    plugin_id: PluginId,
+    launcher: AccountId,
+    // number of launches reported by the launcher
+    total_installations: u32,
+    latest_version: Version,
+    // number of spaces having the plugin installed
+    spaces_using: u32,
+  }
+
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct SpaceInfoSummary {
+    space_id: SpaceId,
+    name: String,
+    logo: Option<ImageSource>,
+    members_count: u32,
+  }
+
+  type SpaceInfoSummariesPage = Pagination<SpaceInfoSummary>;
+
+  #[derive(Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub struct SpaceTemplate {
+    name: String,
+    info_preset: SpaceInfo,
+    config_preset: SpaceConfig,
+    plugins_preset: Vec<PluginId>,
+  }
+
+  #[ink(event)]
+  pub struct SpaceDeployed {
+    #[ink(topic)]
+    space_id: SpaceId,
+    #[ink(topic)]
+    owner_id: AccountId,
+    endowment: Balance,
+  }
+
+  /// Spaces are not upgraded automatically, they opt in by upgrading their plugins
+  #[ink(event)]
+  pub struct PluginUpgradeAvailable {
+    #[ink(topic)]
+    plugin_id: PluginId,
+    new_version: Version,
+  }
+
+  #[ink(event)]
+  pub struct Withdrawn {
+    #[ink(topic)]
+    to: AccountId,
+    amount: Balance,
+  }
 
   #[ink(storage)]
   #[derive(Default, Storage)]
@@ -63,19 +125,54 @@ mod motherspace {
 
     members_to_spaces: Mapping<AccountId, Vec<SpaceId>>,
 
+    // Append-only index of every account that has ever been a member of a space,
+    // accounts are kept in the index even after leaving all spaces
+    member_index: Mapping<u32, AccountId>,
+    member_to_index: Mapping<AccountId, u32>,
+    members_nonce: Lazy<Nonce>,
+
     deployed_spaces: Mapping<SpaceId, AccountId>,
+    owner_to_spaces: Mapping<AccountId, Vec<SpaceId>>,
+    space_endowments: Mapping<SpaceId, Balance>,
     index_to_space: Mapping<u32, SpaceId>,
+    space_member_count: Mapping<SpaceId, u32>,
+    // (name, logo) of spaces, updated by spaces whenever their info changes
+    space_info_cache: Mapping<SpaceId, (String, Option<ImageSource>)>,
+    // spaces archived by their owners, hidden from the default listing
+    archived_spaces: Lazy<Vec<SpaceId>>,
     spaces_count: Lazy<u32>,
 
     ids_to_plugin_launchers: Mapping<PluginId, AccountId>,
     plugin_launchers: Mapping<PluginIndex, PluginId>,
     plugins_nonce: Lazy<Nonce>,
+    plugin_to_spaces: Mapping<PluginId, Vec<SpaceId>>,
+    // plugins installed via this motherspace, used to rank plugins without querying every launcher
+    plugin_installations: Mapping<PluginId, u32>,
+    plugin_dependencies: Mapping<PluginId, Vec<PluginId>>,
+    // plugin ids must be derived from launcher addresses, enabled by default
+    strict_plugin_id_enforcement: Lazy<bool>,
+    // spaces opted out of plugin upgrades
+    space_plugin_pinned_versions: Mapping<(SpaceId, PluginId), Version>,
+
+    templates: Mapping<u32, SpaceTemplate>,
+    templates_count: Lazy<u32>,
+
+    globally_paused: Lazy<bool>,
+
+    // reserved space names, stored in lowercase
+    reserved_names: Mapping<String, bool>,
+
+    whitelist_enabled: Lazy<bool>,
+    deployer_whitelist: Mapping<AccountId, bool>,
 
     #[storage_field]
     ownable: ownable::Data,
+    #[storage_field]
+    migration: migratable::Data,
   }
 
   impl CodeHash for MotherSpace {}
+  impl Migratable for MotherSpace {}
 
   impl MotherSpace {
     #[ink(constructor)]
@@ -83,6 +180,11 @@ mod motherspace {
       let mut one = MotherSpace::default();
       ownable::Internal::_init_with_owner(&mut one, owner_id);
       one.upgrade_space_code_impl(space_code);
+      one._set_storage_version(LATEST_STORAGE_VERSION);
+
+      for name in DEFAULT_RESERVED_NAMES {
+        one.reserved_names.insert(String::from(name), &true);
+      }
 
       one
     }
@@ -95,23 +197,71 @@ mod motherspace {
       Ok(())
     }
 
+    /// Run the next migration step after a code upgrade, returns the new storage version
+    /// Each step must be bounded, call this repeatedly until it reaches the latest version
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn migrate(&mut self) -> MotherSpaceResult<StorageVersion> {
+      let current_version = self._storage_version();
+      ensure!(current_version < LATEST_STORAGE_VERSION, MotherSpaceError::Custom(String::from("Storage is already up to date!")));
+
+      match current_version {
+        // Initial version, there's no data to migrate
+        0 => {}
+        _ => return Err(MotherSpaceError::Custom(format!("No migration found for version {}", current_version))),
+      }
+
+      let next_version = current_version.saturating_add(1);
+      self._set_storage_version(next_version);
+
+      Ok(next_version)
+    }
+
+    /// Withdraw native balance of the contract, defaults to the owner & the full withdrawable balance
+    /// The existential deposit is always kept to keep the contract alive
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn withdraw(&mut self, to: Option<AccountId>, amount: Option<Balance>) -> MotherSpaceResult<Balance> {
+      let to = to.unwrap_or(self.env().caller());
+      let withdrawable = self.withdrawable_balance();
+      let amount = amount.unwrap_or(withdrawable);
+
+      ensure!(amount > 0 && amount <= withdrawable, MotherSpaceError::InsufficientBalance);
+      self.env().transfer(to, amount).map_err(|_| MotherSpaceError::WithdrawFailed)?;
+
+      self.env().emit_event(Withdrawn { to, amount });
+
+      Ok(amount)
+    }
+
+    /// Free balance that can be withdrawn without dropping below the existential deposit
+    #[ink(message)]
+    pub fn withdrawable_balance(&self) -> Balance {
+      self.env().balance().saturating_sub(self.env().minimum_balance())
+    }
+
     #[ink(message)]
     pub fn latest_space_code(&self) -> Hash {
       self.latest_space_code_impl()
     }
 
-    #[ink(message)]
+    /// Deploy a new space, transferred value is forwarded as the endowment of the new space
+    #[ink(message, payable)]
     pub fn deploy_new_space(&mut self, info: SpaceInfo, config: Option<SpaceConfig>,
-                            owner: Option<AccountId>, plugins: Option<Vec<PluginId>>) -> MotherSpaceResult<(SpaceId, Vec<(PluginId, AccountId)>)> {
+                            owner: Option<AccountId>, plugins: Option<Vec<PluginId>>) -> MotherSpaceResult<(SpaceId, Vec<(PluginId, PluginInstallStatus)>)> {
+      ensure!(self.is_whitelisted(Self::env().caller()), MotherSpaceError::UnAuthorized);
+      ensure!(!self.is_name_reserved(info.name.clone()), MotherSpaceError::Custom(String::from("Space name is reserved")));
+
       let new_spaces_count = self.spaces_count.get_or_default();
 
       let motherspace_id = Self::env().account_id();
       let owner_id = owner.unwrap_or(Self::env().caller());
+      let endowment = Self::env().transferred_value();
 
       let new_space: SpaceRef = build_create::<SpaceRef>()
         .code_hash(self.latest_space_code_impl())
         .gas_limit(0)
-        .endowment(0)
+        .endowment(endowment)
         .exec_input(
           ExecutionInput::new(Selector::new(ink::selector_bytes!("new")))
             .push_arg(motherspace_id)
@@ -126,32 +276,175 @@ mod motherspace {
       let new_space_id = new_space.to_account_id();
 
       self.deployed_spaces.insert(new_space_id, &owner_id);
+      self.add_owned_space_impl(owner_id, new_space_id);
       self.index_to_space.insert(new_spaces_count, &new_space_id);
+      self.space_info_cache.insert(new_space_id, &(info.name.clone(), info.logo.clone()));
+      if endowment > 0 {
+        self.space_endowments.insert(new_space_id, &endowment);
+      }
 
       let next_spaces_count = new_spaces_count.saturating_add(1);
       self.spaces_count.set(&next_spaces_count);
 
       self.add_space_member_impl(new_space_id, owner_id);
 
-      // TODO should emit errors if plugins fail to deploy
-      let deployed_plugins = match plugins {
-        Some(plugin_ids) => self.install_plugins_impl(new_space_id, plugin_ids).unwrap(),
+      self.env().emit_event(SpaceDeployed {
+        space_id: new_space_id,
+        owner_id,
+        endowment,
+      });
+
+      let install_results = match plugins {
+        Some(plugin_ids) => self.install_plugins_impl(new_space_id, plugin_ids.into_iter().map(|id| (id, Vec::new())).collect())?,
         None => Vec::new()
       };
 
-      Ok((new_space_id, deployed_plugins))
+      Ok((new_space_id, install_results))
+    }
+
+    /// Register a space template with preset info, config & plugins
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn register_template(&mut self, template: SpaceTemplate) -> MotherSpaceResult<u32> {
+      ensure!(!template.name.is_empty(), MotherSpaceError::Custom(String::from("Template name cannot be empty")));
+
+      let new_template_id = self.templates_count.get_or_default();
+      self.templates.insert(new_template_id, &template);
+      self.templates_count.set(&new_template_id.checked_add(1).expect("Exceeds number of templates"));
+
+      Ok(new_template_id)
+    }
+
+    #[ink(message)]
+    pub fn template(&self, id: u32) -> Option<SpaceTemplate> {
+      self.templates.get(id)
+    }
+
+    #[ink(message)]
+    pub fn templates_count(&self) -> u32 {
+      self.templates_count.get_or_default()
+    }
+
+    /// Deploy a new space from a template with a custom name
+    #[ink(message, payable)]
+    pub fn deploy_from_template(&mut self, template_id: u32, custom_name: String,
+                                owner: Option<AccountId>) -> MotherSpaceResult<(SpaceId, Vec<(PluginId, PluginInstallStatus)>)> {
+      let template = self.templates.get(template_id).ok_or(MotherSpaceError::Custom(String::from("Template not found")))?;
+
+      let info = SpaceInfo {
+        name: custom_name,
+        ..template.info_preset
+      };
+
+      self.deploy_new_space(info, Some(template.config_preset), owner, Some(template.plugins_preset))
+    }
+
+    /// Reserve a space name to prevent impersonation, names are case-insensitive
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn reserve_name(&mut self, name: String) -> MotherSpaceResult<()> {
+      self.reserved_names.insert(name.to_lowercase(), &true);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn unreserve_name(&mut self, name: String) -> MotherSpaceResult<()> {
+      self.reserved_names.remove(name.to_lowercase());
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn is_name_reserved(&self, name: String) -> bool {
+      self.reserved_names.contains(name.to_lowercase())
+    }
+
+    /// Pause writes of all spaces in case of emergency
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn emergency_pause(&mut self) -> MotherSpaceResult<()> {
+      self.globally_paused.set(&true);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn emergency_unpause(&mut self) -> MotherSpaceResult<()> {
+      self.globally_paused.set(&false);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn is_globally_paused(&self) -> bool {
+      self.globally_paused.get_or_default()
+    }
+
+    #[ink(message)]
+    pub fn whitelist_enabled(&self) -> bool {
+      self.whitelist_enabled.get_or_default()
+    }
+
+    /// Restrict space deployment to whitelisted accounts
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn enable_deployer_whitelist(&mut self) -> MotherSpaceResult<()> {
+      self.whitelist_enabled.set(&true);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn disable_deployer_whitelist(&mut self) -> MotherSpaceResult<()> {
+      self.whitelist_enabled.set(&false);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn add_to_whitelist(&mut self, who: AccountId) -> MotherSpaceResult<()> {
+      self.deployer_whitelist.insert(who, &true);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn remove_from_whitelist(&mut self, who: AccountId) -> MotherSpaceResult<()> {
+      self.deployer_whitelist.remove(who);
+
+      Ok(())
+    }
+
+    /// Check if an account can deploy spaces, owner is always allowed
+    #[ink(message)]
+    pub fn is_whitelisted(&self, who: AccountId) -> bool {
+      !self.whitelist_enabled()
+        || self.deployer_whitelist.contains(who)
+        || Ownable::owner(self) == Some(who)
     }
 
     #[ink(message)]
-    pub fn list_spaces(&self, from: u32, per_page: u32) -> SpacesPage {
+    pub fn list_spaces(&self, from: u32, per_page: u32, include_archived: bool) -> SpacesPage {
       let last_position = from.saturating_add(per_page);
       let per_page = per_page.min(50); // limit per page at max 50 items
       let current_spaces_count = self.spaces_count.get_or_default();
+      let archived_spaces = if include_archived { Vec::new() } else { self.archived_spaces.get_or_default() };
 
+      // archived spaces are skipped within the page, so a page can have less than `per_page` items
       let mut space_records = Vec::new();
       for index in (from as usize)..(last_position.min(current_spaces_count) as usize) {
         let bounded_index = index as u32;
         if let Some(space_id) = self.index_to_space.get(bounded_index) {
+          if archived_spaces.contains(&space_id) {
+            continue;
+          }
+
           space_records.push((space_id, self._space_code_hash(space_id)))
         }
       }
@@ -161,6 +454,81 @@ mod motherspace {
         from,
         per_page,
         has_next_page: last_position < current_spaces_count,
+        total: current_spaces_count.saturating_sub(archived_spaces.len() as u32),
+      }
+    }
+
+    #[ink(message)]
+    pub fn list_archived_spaces(&self, from: u32, per_page: u32) -> SpacesPage {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let space_ids = self.archived_spaces.get_or_default();
+      let total = space_ids.len() as u32;
+
+      let space_records = space_ids.iter()
+        .skip(from as usize)
+        .take(per_page as usize)
+        .map(|&space_id| (space_id, self._space_code_hash(space_id)))
+        .collect();
+
+      SpacesPage {
+        items: space_records,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      }
+    }
+
+    #[ink(message)]
+    pub fn is_space_archived(&self, space_id: SpaceId) -> bool {
+      self.archived_spaces.get_or_default().contains(&space_id)
+    }
+
+    /// Spaces call this when they are archived by their owners
+    #[ink(message)]
+    pub fn notify_space_archived(&mut self, space_id: SpaceId) -> MotherSpaceResult<()> {
+      let caller = self.env().caller();
+      ensure!(
+        caller == space_id && self.is_deployed_space_impl(space_id),
+        MotherSpaceError::Custom(String::from("Only deployed spaces can call this!"))
+      );
+
+      let mut archived_spaces = self.archived_spaces.get_or_default();
+      if !archived_spaces.contains(&space_id) {
+        archived_spaces.push(space_id);
+        self.archived_spaces.set(&archived_spaces);
+      }
+
+      Ok(())
+    }
+
+    /// List spaces with their cached info, no cross-contract calls involved
+    #[ink(message)]
+    pub fn list_spaces_with_info(&self, from: u32, per_page: u32) -> SpaceInfoSummariesPage {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let current_spaces_count = self.spaces_count.get_or_default();
+
+      let mut summaries = Vec::new();
+      for index in (from as usize)..(last_position.min(current_spaces_count) as usize) {
+        if let Some(space_id) = self.index_to_space.get(index as u32) {
+          let (name, logo) = self.space_info_cache.get(space_id).unwrap_or_default();
+
+          summaries.push(SpaceInfoSummary {
+            space_id,
+            name,
+            logo,
+            members_count: self.space_member_count.get(space_id).unwrap_or_default(),
+          });
+        }
+      }
+
+      SpaceInfoSummariesPage {
+        items: summaries,
+        from,
+        per_page,
+        has_next_page: last_position < current_spaces_count,
         total: current_spaces_count,
       }
     }
@@ -175,6 +543,44 @@ mod motherspace {
         .collect()
     }
 
+    #[ink(message)]
+    pub fn member_spaces_count(&self, who: AccountId) -> u32 {
+      self.members_to_spaces.get(who).unwrap_or_default().len() as u32
+    }
+
+    #[ink(message)]
+    pub fn is_member_of_any_space(&self, who: AccountId) -> bool {
+      self.members_to_spaces.contains(who)
+    }
+
+    /// List all accounts that have ever been a member of a space
+    #[ink(message)]
+    pub fn list_all_members(&self, from: u32, per_page: u32) -> MembersPage {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let current_members_nonce = self.members_nonce.get_or_default();
+
+      let mut members = Vec::new();
+      for index in (from as usize)..(last_position.min(current_members_nonce) as usize) {
+        if let Some(member_id) = self.member_index.get(index as u32) {
+          members.push(member_id);
+        }
+      }
+
+      MembersPage {
+        items: members,
+        from,
+        per_page,
+        has_next_page: last_position < current_members_nonce,
+        total: current_members_nonce,
+      }
+    }
+
+    #[ink(message)]
+    pub fn all_members_count(&self) -> u32 {
+      self.members_nonce.get_or_default()
+    }
+
     fn _space_code_hash(&self, space_id: SpaceId) -> Hash {
       build_call::<DefaultEnvironment>()
         .call(space_id)
@@ -206,6 +612,69 @@ mod motherspace {
       Ok(())
     }
 
+    /// Spaces call this when their ownership is transferred or renounced (`new_owner` is None),
+    /// the previous owner is taken from the records instead of being trusted from the space
+    #[ink(message)]
+    pub fn notify_owner_change(&mut self, new_owner: Option<AccountId>) -> MotherSpaceResult<()> {
+      let space_id = self.env().caller();
+      let old_owner = self.deployed_spaces.get(space_id)
+        .ok_or(MotherSpaceError::Custom(String::from("Only deployed spaces can call this!")))?;
+
+      let mut old_owner_spaces = self.owner_to_spaces.get(old_owner).unwrap_or_default();
+      old_owner_spaces.retain(|&id| id != space_id);
+      if old_owner_spaces.is_empty() {
+        self.owner_to_spaces.remove(old_owner);
+      } else {
+        self.owner_to_spaces.insert(old_owner, &old_owner_spaces);
+      }
+
+      // renounced spaces keep their last owner as deployed space record
+      if let Some(new_owner) = new_owner {
+        self.add_owned_space_impl(new_owner, space_id);
+        self.deployed_spaces.insert(space_id, &new_owner);
+      }
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn spaces_by_owner(&self, who: AccountId, from: u32, per_page: u32) -> SpacesPage {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let space_ids = self.owner_to_spaces.get(who).unwrap_or_default();
+      let total = space_ids.len() as u32;
+
+      let space_records = space_ids.iter()
+        .skip(from as usize)
+        .take(per_page as usize)
+        .map(|&space_id| (space_id, self._space_code_hash(space_id)))
+        .collect();
+
+      SpacesPage {
+        items: space_records,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      }
+    }
+
+    #[ink(message)]
+    pub fn owned_spaces_count(&self, who: AccountId) -> u32 {
+      self.owner_to_spaces.get(who).unwrap_or_default().len() as u32
+    }
+
+    /// Spaces call this to refresh their cached info
+    #[ink(message)]
+    pub fn notify_info_update(&mut self, name: String, logo: Option<ImageSource>) -> MotherSpaceResult<()> {
+      let space_id = self.env().caller();
+      ensure!(self.is_deployed_space_impl(space_id), MotherSpaceError::Custom(String::from("Only deployed spaces can call this!")));
+
+      self.space_info_cache.insert(space_id, &(name, logo));
+
+      Ok(())
+    }
+
     #[ink(message)]
     pub fn spaces_count(&self) -> u32 {
       self.spaces_count.get_or_default()
@@ -216,6 +685,12 @@ mod motherspace {
       self.is_deployed_space_impl(space_id)
     }
 
+    /// Initial endowment forwarded to a space at deployment
+    #[ink(message)]
+    pub fn space_endowment(&self, space_id: SpaceId) -> Balance {
+      self.space_endowments.get(space_id).unwrap_or_default()
+    }
+
     #[ink(message)]
     pub fn space_code_hash(&self, space_id: SpaceId) -> MotherSpaceResult<Hash> {
       ensure!(self.is_deployed_space_impl(space_id), MotherSpaceError::SpaceNotFound);
@@ -223,6 +698,38 @@ mod motherspace {
       Ok(self._space_code_hash(space_id))
     }
 
+    /// Check if an account is an active member of a space
+    #[ink(message)]
+    pub fn is_space_member(&self, space_id: SpaceId, who: AccountId) -> MotherSpaceResult<bool> {
+      ensure!(self.is_deployed_space_impl(space_id), MotherSpaceError::SpaceNotFound);
+
+      self.is_active_member_impl(space_id, who)
+    }
+
+    /// Check active membership of an account in multiple spaces, limit at max 50 spaces
+    /// None if the space is not found or fails to respond
+    #[ink(message)]
+    pub fn is_member_of_spaces(&self, space_ids: Vec<SpaceId>, who: AccountId) -> Vec<(SpaceId, Option<bool>)> {
+      space_ids.into_iter()
+        .take(MAX_SPACES_MEMBERSHIP_QUERY)
+        .map(|space_id| (space_id, self.is_space_member(space_id, who).ok()))
+        .collect()
+    }
+
+    fn is_active_member_impl(&self, space_id: SpaceId, who: AccountId) -> MotherSpaceResult<bool> {
+      build_call::<DefaultEnvironment>()
+        .call(space_id)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("is_active_member")))
+            .push_arg(who)
+        )
+        .returns::<bool>()
+        .try_invoke()
+        .map_err(|_| MotherSpaceError::SpaceCallFailed)?
+        .map_err(|_| MotherSpaceError::SpaceCallFailed)
+    }
+
     #[ink(message)]
     pub fn plugins_count(&self) -> u32 {
       self.plugins_nonce.get_or_default()
@@ -230,10 +737,25 @@ mod motherspace {
 
     #[ink(message)]
     #[modifiers(only_owner)]
-    pub fn register_plugin_launcher(&mut self, plugin_id: PluginId, launcher_address: AccountId) -> MotherSpaceResult<PluginIndex> {
+    pub fn register_plugin_launcher(&mut self, plugin_id: PluginId, launcher_address: AccountId, deps: Vec<PluginId>) -> MotherSpaceResult<PluginIndex> {
       // For now only owner can register plugin launcher
       // Later we can add a mechanism for anyone can submit a plugin application for approval
       ensure!(!self.ids_to_plugin_launchers.contains(plugin_id), MotherSpaceError::PluginIdExisted);
+      if self.strict_plugin_id_enforcement() {
+        ensure!(
+          self.validate_plugin_id(plugin_id, launcher_address),
+          MotherSpaceError::Custom(String::from("PluginId doesn't match launcher address checksum"))
+        );
+      }
+      self.ensure_valid_launcher_impl(launcher_address)?;
+      ensure!(
+        deps.iter().all(|&dep| dep != plugin_id && self.ids_to_plugin_launchers.contains(dep)),
+        MotherSpaceError::Custom(String::from("Plugin dependencies must be registered before the plugin!"))
+      );
+
+      if !deps.is_empty() {
+        self.plugin_dependencies.insert(plugin_id, &deps);
+      }
 
       let new_plugin_id = self.plugins_nonce.get_or_default();
       self.plugin_launchers.insert(new_plugin_id, &plugin_id);
@@ -243,12 +765,38 @@ mod motherspace {
       Ok(new_plugin_id)
     }
 
-    /// Update plugin launcher address or remove it
-    // #[ink(message)]
-    // [modifiers(only_owner)]
-    // pub fn update_plugin_launcher(&mut self, plugin_id: PluginId, launcher_address: Option<AccountId>) -> MotherSpaceResult<()> {
-    //   let ZERO_ACCOUNT: AccountId = [0; 32].into();
-    //   ensure!(self.ids_to_plugin_launchers.contains(plugin_id), MotherSpaceError::PluginNotFound);
+    /// Plugin id derived from the launcher address, first 4 bytes of its blake2x256 hash
+    #[ink(message)]
+    pub fn compute_plugin_id(&self, launcher_address: AccountId) -> PluginId {
+      let hash = self.env().hash_encoded::<Blake2x256, _>(&launcher_address);
+
+      [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    #[ink(message)]
+    pub fn validate_plugin_id(&self, id: PluginId, launcher: AccountId) -> bool {
+      self.compute_plugin_id(launcher) == id
+    }
+
+    #[ink(message)]
+    pub fn strict_plugin_id_enforcement(&self) -> bool {
+      self.strict_plugin_id_enforcement.get().unwrap_or(true)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn set_strict_plugin_id_enforcement(&mut self, enabled: bool) -> MotherSpaceResult<()> {
+      self.strict_plugin_id_enforcement.set(&enabled);
+
+      Ok(())
+    }
+
+    /// Update plugin launcher address or remove it
+    // #[ink(message)]
+    // [modifiers(only_owner)]
+    // pub fn update_plugin_launcher(&mut self, plugin_id: PluginId, launcher_address: Option<AccountId>) -> MotherSpaceResult<()> {
+    //   let ZERO_ACCOUNT: AccountId = [0; 32].into();
+    //   ensure!(self.ids_to_plugin_launchers.contains(plugin_id), MotherSpaceError::PluginNotFound);
     //   let new_address = launcher_address.unwrap_or(ZERO_ACCOUNT);
     //   self.ids_to_plugin_launchers.insert(plugin_id, &new_address);
     //
@@ -272,6 +820,75 @@ mod motherspace {
       launchers
     }
 
+    /// Adoption statistics of registered plugins, launchers are queried so at max 20 plugins per page
+    #[ink(message)]
+    pub fn plugin_ecosystem_stats(&self, from: u32, per_page: u32) -> Vec<PluginEcosystemStats> {
+      let per_page = per_page.min(MAX_PLUGIN_STATS_QUERY);
+      let last_position = from.saturating_add(per_page).min(self.plugins_count());
+
+      (from..last_position)
+        .filter_map(|idx| self.plugin_launchers.get(idx))
+        .filter_map(|plugin_id| self.ids_to_plugin_launchers.get(plugin_id).map(|launcher| (plugin_id, launcher)))
+        .map(|(plugin_id, launcher)| PluginEcosystemStats {
+          plugin_id,
+          launcher,
+          total_installations: self.launcher_query_impl::<u32>(launcher, ink::selector_bytes!("PluginLauncher::launches_count")).unwrap_or_default(),
+          latest_version: self.launcher_query_impl::<Version>(launcher, ink::selector_bytes!("PluginLauncher::latest_plugin_version")).unwrap_or_default(),
+          spaces_using: self.plugin_to_spaces.get(plugin_id).unwrap_or_default().len() as u32,
+        })
+        .collect()
+    }
+
+    /// Top plugins by number of installations via this motherspace, limit at max 50 plugins
+    #[ink(message)]
+    pub fn most_installed_plugins(&self, top_n: u32) -> Vec<(PluginId, u32)> {
+      let top_n = top_n.min(50);
+      let mut installations: Vec<(PluginId, u32)> = self.plugin_launchers()
+        .into_iter()
+        .map(|(plugin_id, _)| (plugin_id, self.plugin_installations.get(plugin_id).unwrap_or_default()))
+        .collect();
+
+      installations.sort_by(|a, b| b.1.cmp(&a.1));
+      installations.truncate(top_n as usize);
+
+      installations
+    }
+
+    /// Ensure the launcher is reachable and was configured with this motherspace
+    fn ensure_valid_launcher_impl(&self, launcher: AccountId) -> MotherSpaceResult<()> {
+      let launcher_motherspace_id = self.launcher_query_impl::<AccountId>(launcher, ink::selector_bytes!("PluginLauncher::motherspace_id"))
+        .ok_or(MotherSpaceError::Custom(String::from("Cannot query motherspace id of the launcher!")))?;
+      ensure!(
+        launcher_motherspace_id == self.env().account_id(),
+        MotherSpaceError::Custom(String::from("Launcher is configured with a different motherspace!"))
+      );
+
+      self.launcher_query_impl::<Hash>(launcher, ink::selector_bytes!("PluginLauncher::latest_plugin_code"))
+        .ok_or(MotherSpaceError::Custom(String::from("Cannot query latest plugin code of the launcher!")))?;
+
+      Ok(())
+    }
+
+    fn launcher_query_impl<R: scale::Decode>(&self, launcher: AccountId, selector: [u8; 4]) -> Option<R> {
+      build_call::<DefaultEnvironment>()
+        .call(launcher)
+        .gas_limit(0)
+        .exec_input(ExecutionInput::new(Selector::new(selector)))
+        .returns::<R>()
+        .try_invoke()
+        .ok()
+        .and_then(|rs| rs.ok())
+    }
+
+    /// Declared dependencies of all registered plugins
+    #[ink(message)]
+    pub fn plugin_dependency_graph(&self) -> Vec<(PluginId, Vec<PluginId>)> {
+      self.plugin_launchers()
+        .into_iter()
+        .map(|(plugin_id, _)| (plugin_id, self.plugin_dependencies.get(plugin_id).unwrap_or_default()))
+        .collect()
+    }
+
     #[ink(message)]
     pub fn latest_plugin_code(&self, plugin_id: PluginId) -> MotherSpaceResult<Hash> {
       let launcher = self.ids_to_plugin_launchers.get(plugin_id).ok_or(MotherSpaceError::PluginNotFound)?;
@@ -288,6 +905,31 @@ mod motherspace {
       Ok(result)
     }
 
+    /// Get latest plugin codes of multiple plugins in one go, limit at max 20 plugin ids
+    /// None if the plugin is not found or its launcher fails to respond
+    #[ink(message)]
+    pub fn latest_plugin_codes(&self, plugin_ids: Vec<PluginId>) -> Vec<(PluginId, Option<Hash>)> {
+      plugin_ids.into_iter()
+        .take(MAX_PLUGIN_CODES_QUERY)
+        .map(|plugin_id| {
+          let maybe_code = self.ids_to_plugin_launchers.get(plugin_id).and_then(|launcher| {
+            build_call::<DefaultEnvironment>()
+              .call(launcher)
+              .gas_limit(0)
+              .exec_input(
+                ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::latest_plugin_code")))
+              )
+              .returns::<Hash>()
+              .try_invoke()
+              .ok()
+              .and_then(|rs| rs.ok())
+          });
+
+          (plugin_id, maybe_code)
+        })
+        .collect()
+    }
+
     #[ink(message)]
     #[modifiers(only_owner)]
     pub fn upgrade_plugin_code(&mut self, plugin_id: PluginId, new_code_hash: Hash) -> MotherSpaceResult<Version> {
@@ -303,57 +945,113 @@ mod motherspace {
         .returns::<Version>()
         .invoke();
 
+      self.env().emit_event(PluginUpgradeAvailable { plugin_id, new_version });
+
       Ok(new_version)
     }
 
+    /// Pin a plugin of the space at the current launcher version
+    #[ink(message)]
+    pub fn pin_plugin_version(&mut self, space_id: SpaceId, plugin_id: PluginId) -> MotherSpaceResult<()> {
+      ensure!(self.is_deployed_space(space_id), MotherSpaceError::SpaceNotFound);
+      ensure!(self.space_owner_impl(space_id) == Some(self.env().caller()), MotherSpaceError::UnAuthorized);
+
+      let launcher = self.ids_to_plugin_launchers.get(plugin_id).ok_or(MotherSpaceError::PluginNotFound)?;
+      let current_version = self.launcher_query_impl::<Version>(launcher, ink::selector_bytes!("PluginLauncher::latest_plugin_version"))
+        .ok_or(MotherSpaceError::PluginNotFound)?;
+
+      self.space_plugin_pinned_versions.insert((space_id, plugin_id), &current_version);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn unpin_plugin_version(&mut self, space_id: SpaceId, plugin_id: PluginId) -> MotherSpaceResult<()> {
+      ensure!(self.is_deployed_space(space_id), MotherSpaceError::SpaceNotFound);
+      ensure!(self.space_owner_impl(space_id) == Some(self.env().caller()), MotherSpaceError::UnAuthorized);
+
+      self.space_plugin_pinned_versions.remove((space_id, plugin_id));
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn pinned_plugin_version(&self, space_id: SpaceId, plugin_id: PluginId) -> Option<Version> {
+      self.space_plugin_pinned_versions.get((space_id, plugin_id))
+    }
 
-    /// Install plugins
+    /// Check if a newer plugin version is available for the space, always false for pinned plugins
     #[ink(message)]
-    pub fn install_plugins(&mut self, space_id: SpaceId, plugins: Vec<PluginId>) -> MotherSpaceResult<Vec<(PluginId, AccountId)>> {
-      if !self.is_deployed_space(space_id) {
-        return Err(MotherSpaceError::SpaceNotFound);
+    pub fn is_plugin_upgrade_available(&self, space_id: SpaceId, plugin_id: PluginId, current_version: Version) -> bool {
+      if self.space_plugin_pinned_versions.contains((space_id, plugin_id)) {
+        return false;
       }
 
-      // Ensure space owner
-      let space_owner_id = build_call::<DefaultEnvironment>()
+      self.ids_to_plugin_launchers.get(plugin_id)
+        .and_then(|launcher| self.launcher_query_impl::<Version>(launcher, ink::selector_bytes!("PluginLauncher::latest_plugin_version")))
+        .map_or(false, |latest_version| latest_version > current_version)
+    }
+
+
+    /// Install plugins, returns install status of each plugin
+    #[ink(message)]
+    pub fn install_plugins(&mut self, space_id: SpaceId, plugins: Vec<PluginId>) -> MotherSpaceResult<Vec<(PluginId, PluginInstallStatus)>> {
+      self.install_plugins_with_config(space_id, plugins.into_iter().map(|id| (id, Vec::new())).collect())
+    }
+
+    /// Install plugins with initialization data passed to each plugin constructor
+    #[ink(message)]
+    pub fn install_plugins_with_config(&mut self, space_id: SpaceId, plugins: Vec<(PluginId, Vec<u8>)>) -> MotherSpaceResult<Vec<(PluginId, PluginInstallStatus)>> {
+      ensure!(self.is_deployed_space(space_id), MotherSpaceError::SpaceNotFound);
+      ensure!(self.space_owner_impl(space_id) == Some(self.env().caller()), MotherSpaceError::UnAuthorized);
+
+      self.install_plugins_impl(space_id, plugins)
+    }
+
+    fn space_owner_impl(&self, space_id: SpaceId) -> Option<AccountId> {
+      build_call::<DefaultEnvironment>()
         .call(space_id)
         .gas_limit(0)
         .exec_input(
           ExecutionInput::new(Selector::new(ink::selector_bytes!("Ownable::owner")))
         )
         .returns::<Option<AccountId>>()
-        .invoke();
-
-      ensure!(space_owner_id == Some(self.env().caller()), MotherSpaceError::UnAuthorized);
-
-      self.install_plugins_impl(space_id, plugins)
+        .invoke()
     }
 
-    fn install_plugins_impl(&mut self, space_id: SpaceId, plugins: Vec<PluginId>) -> MotherSpaceResult<Vec<(PluginId, AccountId)>> {
+    /// Launch & attach plugins to a space, an error is returned if the space cannot attach the launched plugins
+    /// so the whole call is reverted instead of leaving orphaned plugins behind
+    fn install_plugins_impl(&mut self, space_id: SpaceId, plugins: Vec<(PluginId, Vec<u8>)>) -> MotherSpaceResult<Vec<(PluginId, PluginInstallStatus)>> {
+      let mut install_results: Vec<(PluginId, PluginInstallStatus)> = Vec::new();
       let mut deployed_plugins: Vec<(PluginId, AccountId)> = Vec::new();
-      for plugin_id in plugins {
-        let opt_launcher = self.ids_to_plugin_launchers.get(plugin_id);
-        if let Some(launcher_address) = opt_launcher {
-          let plugin_address_rs = build_call::<DefaultEnvironment>()
-            .call(launcher_address)
-            .gas_limit(0)
-            .exec_input(
-              ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::launch")))
-                .push_arg(space_id)
-            )
-            .returns::<MotherSpaceResult<AccountId>>()
-            .invoke();
-
-          if let Ok(plugin_address) = plugin_address_rs {
-            deployed_plugins.push((plugin_id, plugin_address));
-          } else {
-            return Err(MotherSpaceError::PluginLaunchFailed);
-          }
+
+      let installed_plugins = self.space_plugin_ids_impl(space_id);
+      let mut ordered_plugins: Vec<PluginId> = Vec::new();
+      for (plugin_id, _) in plugins.iter() {
+        self.resolve_plugin_dependencies_impl(*plugin_id, &installed_plugins, &mut ordered_plugins);
+      }
+
+      for plugin_id in ordered_plugins {
+        // dependencies which are not requested are launched without init data
+        let init_data = plugins.iter()
+          .find(|(id, _)| *id == plugin_id)
+          .map(|(_, data)| data.clone())
+          .unwrap_or_default();
+
+        let status = match self.ids_to_plugin_launchers.get(plugin_id) {
+          Some(launcher_address) => self.launch_plugin_impl(launcher_address, space_id, init_data),
+          None => PluginInstallStatus::LauncherNotFound,
+        };
+
+        if let PluginInstallStatus::Installed(plugin_address) = status {
+          deployed_plugins.push((plugin_id, plugin_address));
         }
+
+        install_results.push((plugin_id, status));
       }
 
       if deployed_plugins.is_empty() {
-        return Ok(deployed_plugins);
+        return Ok(install_results);
       }
 
       ::ink::env::debug_println!("Deployed plugins {:?}", deployed_plugins);
@@ -365,13 +1063,92 @@ mod motherspace {
           ExecutionInput::new(Selector::new(ink::selector_bytes!("attach_plugins")))
             .push_arg(&deployed_plugins)
         )
-        .returns::<MotherSpaceResult<()>>()
-        .invoke();
+        .returns::<Result<(), SpaceError>>()
+        .try_invoke();
+
+      ensure!(matches!(result, Ok(Ok(Ok(())))), MotherSpaceError::Custom(String::from("Cannot attach plugins to space")));
+
+      for (plugin_id, _) in deployed_plugins {
+        let installations = self.plugin_installations.get(plugin_id).unwrap_or_default();
+        self.plugin_installations.insert(plugin_id, &installations.saturating_add(1));
+
+        let mut spaces = self.plugin_to_spaces.get(plugin_id).unwrap_or_default();
+        if !spaces.contains(&space_id) {
+          spaces.push(space_id);
+          self.plugin_to_spaces.insert(plugin_id, &spaces);
+        }
+      }
 
-      if result.is_ok() {
-        Ok(deployed_plugins)
+      Ok(install_results)
+    }
+
+    /// Resolve plugins to install with their dependencies first, skipping plugins already installed
+    /// Dependencies have to be registered before a plugin & can't change afterwards, so there are no cycles
+    fn resolve_plugin_dependencies_impl(&self, plugin_id: PluginId, installed: &Vec<PluginId>, ordered: &mut Vec<PluginId>) {
+      if installed.contains(&plugin_id) || ordered.contains(&plugin_id) {
+        return;
+      }
+
+      for dep in self.plugin_dependencies.get(plugin_id).unwrap_or_default() {
+        self.resolve_plugin_dependencies_impl(dep, installed, ordered);
+      }
+
+      ordered.push(plugin_id);
+    }
+
+    fn space_plugin_ids_impl(&self, space_id: SpaceId) -> Vec<PluginId> {
+      build_call::<DefaultEnvironment>()
+        .call(space_id)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("plugin_ids")))
+        )
+        .returns::<Vec<PluginId>>()
+        .invoke()
+    }
+
+    fn launch_plugin_impl(&self, launcher_address: AccountId, space_id: SpaceId, init_data: Vec<u8>) -> PluginInstallStatus {
+      // Cheap liveness probe before launching
+      let probe = build_call::<DefaultEnvironment>()
+        .call(launcher_address)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::latest_plugin_code")))
+        )
+        .returns::<Hash>()
+        .try_invoke();
+
+      if !matches!(probe, Ok(Ok(_))) {
+        return PluginInstallStatus::LauncherUnreachable;
+      }
+
+      // Keep using `launch` without init data so launchers without `launch_with_config` still work
+      let launch_result = if init_data.is_empty() {
+        build_call::<DefaultEnvironment>()
+          .call(launcher_address)
+          .gas_limit(0)
+          .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::launch")))
+              .push_arg(space_id)
+          )
+          .returns::<Result<AccountId, LauncherError>>()
+          .try_invoke()
       } else {
-        Err(MotherSpaceError::Custom(format!("Attach plugin failed, error: {:?}", result.unwrap_err())))
+        build_call::<DefaultEnvironment>()
+          .call(launcher_address)
+          .gas_limit(0)
+          .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::launch_with_config")))
+              .push_arg(space_id)
+              .push_arg(init_data)
+          )
+          .returns::<Result<AccountId, LauncherError>>()
+          .try_invoke()
+      };
+
+      match launch_result {
+        Ok(Ok(Ok(plugin_address))) => PluginInstallStatus::Installed(plugin_address),
+        _ => PluginInstallStatus::LaunchFailed,
       }
     }
 
@@ -384,10 +1161,28 @@ mod motherspace {
     }
 
     fn add_space_member_impl(&mut self, space_id: SpaceId, member_id: AccountId) {
+      if !self.member_to_index.contains(member_id) {
+        let new_member_index = self.members_nonce.get_or_default();
+        self.member_index.insert(new_member_index, &member_id);
+        self.member_to_index.insert(member_id, &new_member_index);
+        self.members_nonce.set(&new_member_index.checked_add(1).expect("Exceeds number of members"));
+      }
+
       let mut owner_spaces = self.members_to_spaces.get(member_id).unwrap_or_default();
       if !owner_spaces.contains(&space_id) {
         owner_spaces.push(space_id);
         self.members_to_spaces.insert(member_id, &owner_spaces);
+
+        let members_count = self.space_member_count.get(space_id).unwrap_or_default().saturating_add(1);
+        self.space_member_count.insert(space_id, &members_count);
+      }
+    }
+
+    fn add_owned_space_impl(&mut self, owner_id: AccountId, space_id: SpaceId) {
+      let mut owned_spaces = self.owner_to_spaces.get(owner_id).unwrap_or_default();
+      if !owned_spaces.contains(&space_id) {
+        owned_spaces.push(space_id);
+        self.owner_to_spaces.insert(owner_id, &owned_spaces);
       }
     }
 
@@ -395,7 +1190,14 @@ mod motherspace {
       let owner_spaces = self.members_to_spaces.get(member_id).unwrap_or_default();
       if owner_spaces.contains(&space_id) {
         let new_spaces: Vec<AccountId> = owner_spaces.into_iter().filter(|&x| x != space_id).collect();
-        self.members_to_spaces.insert(member_id, &new_spaces);
+        if new_spaces.is_empty() {
+          self.members_to_spaces.remove(member_id);
+        } else {
+          self.members_to_spaces.insert(member_id, &new_spaces);
+        }
+
+        let members_count = self.space_member_count.get(space_id).unwrap_or_default().saturating_sub(1);
+        self.space_member_count.insert(space_id, &members_count);
       }
     }
 
@@ -405,4 +1207,1062 @@ mod motherspace {
       self.space_codes_nonce.set(&next_space_code_version);
     }
   }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use ink::env::test;
+
+    fn new_motherspace() -> MotherSpace {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      test::set_caller::<DefaultEnvironment>(accounts.alice);
+
+      MotherSpace::new(Hash::from([0x01; 32]), accounts.alice)
+    }
+
+    #[ink::test]
+    fn compute_plugin_id_works() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let motherspace = new_motherspace();
+
+      let mut hash = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+      ink::env::hash_encoded::<Blake2x256, _>(&accounts.django, &mut hash);
+
+      assert_eq!(motherspace.compute_plugin_id(accounts.django), [hash[0], hash[1], hash[2], hash[3]]);
+      assert_ne!(motherspace.compute_plugin_id(accounts.django), motherspace.compute_plugin_id(accounts.eve));
+    }
+
+    #[ink::test]
+    fn validate_plugin_id_works() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let motherspace = new_motherspace();
+      let plugin_id = motherspace.compute_plugin_id(accounts.django);
+
+      assert!(motherspace.validate_plugin_id(plugin_id, accounts.django));
+      assert!(!motherspace.validate_plugin_id(plugin_id, accounts.eve));
+    }
+
+    #[ink::test]
+    fn register_plugin_launcher_rejects_mismatched_id() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut motherspace = new_motherspace();
+      let wrong_id = motherspace.compute_plugin_id(accounts.eve);
+
+      let result = motherspace.register_plugin_launcher(wrong_id, accounts.django, Vec::new());
+      assert!(matches!(result, Err(MotherSpaceError::Custom(message)) if message == "PluginId doesn't match launcher address checksum"));
+      assert_eq!(motherspace.plugins_count(), 0);
+    }
+
+    #[ink::test]
+    fn strict_plugin_id_enforcement_can_be_toggled_by_owner() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut motherspace = new_motherspace();
+      assert!(motherspace.strict_plugin_id_enforcement());
+
+      test::set_caller::<DefaultEnvironment>(accounts.bob);
+      assert!(motherspace.set_strict_plugin_id_enforcement(false).is_err());
+      assert!(motherspace.strict_plugin_id_enforcement());
+
+      test::set_caller::<DefaultEnvironment>(accounts.alice);
+      assert!(motherspace.set_strict_plugin_id_enforcement(false).is_ok());
+      assert!(!motherspace.strict_plugin_id_enforcement());
+    }
+
+    fn space_info(name: &str) -> SpaceInfo {
+      SpaceInfo { name: String::from(name), desc: None, logo: None }
+    }
+
+    #[ink::test]
+    fn anyone_can_deploy_when_whitelist_is_disabled() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let motherspace = new_motherspace();
+
+      assert!(!motherspace.whitelist_enabled());
+      assert!(motherspace.is_whitelisted(accounts.bob));
+      assert!(motherspace.is_whitelisted(accounts.charlie));
+    }
+
+    #[ink::test]
+    fn only_whitelisted_accounts_can_deploy_when_whitelist_is_enabled() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut motherspace = new_motherspace();
+      motherspace.enable_deployer_whitelist().unwrap();
+      motherspace.add_to_whitelist(accounts.bob).unwrap();
+
+      assert!(motherspace.is_whitelisted(accounts.bob));
+      assert!(!motherspace.is_whitelisted(accounts.charlie));
+
+      test::set_caller::<DefaultEnvironment>(accounts.charlie);
+      let result = motherspace.deploy_new_space(space_info("Charlie Space"), None, None, None);
+      assert!(matches!(result, Err(MotherSpaceError::UnAuthorized)));
+
+      test::set_caller::<DefaultEnvironment>(accounts.alice);
+      motherspace.remove_from_whitelist(accounts.bob).unwrap();
+      assert!(!motherspace.is_whitelisted(accounts.bob));
+
+      motherspace.disable_deployer_whitelist().unwrap();
+      assert!(motherspace.is_whitelisted(accounts.charlie));
+    }
+
+    #[ink::test]
+    fn owner_is_always_whitelisted() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut motherspace = new_motherspace();
+      motherspace.enable_deployer_whitelist().unwrap();
+
+      assert!(motherspace.is_whitelisted(accounts.alice));
+    }
+
+    #[ink::test]
+    fn whitelist_is_managed_by_owner() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut motherspace = new_motherspace();
+
+      test::set_caller::<DefaultEnvironment>(accounts.bob);
+      assert!(motherspace.enable_deployer_whitelist().is_err());
+      assert!(motherspace.add_to_whitelist(accounts.bob).is_err());
+      assert!(!motherspace.whitelist_enabled());
+    }
+
+    #[ink::test]
+    fn reserved_names_block_deployment() {
+      let mut motherspace = new_motherspace();
+
+      for name in ["inspace", "InSpace", "MOTHERSPACE", "Admin"] {
+        assert!(motherspace.is_name_reserved(String::from(name)));
+
+        let result = motherspace.deploy_new_space(space_info(name), None, None, None);
+        assert!(matches!(result, Err(MotherSpaceError::Custom(message)) if message == "Space name is reserved"));
+      }
+      assert_eq!(motherspace.spaces_count(), 0);
+    }
+
+    #[ink::test]
+    fn names_can_be_reserved_and_unreserved() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut motherspace = new_motherspace();
+      assert!(!motherspace.is_name_reserved(String::from("Coong")));
+
+      motherspace.reserve_name(String::from("Coong")).unwrap();
+      assert!(motherspace.is_name_reserved(String::from("coong")));
+      assert!(motherspace.is_name_reserved(String::from("COONG")));
+
+      motherspace.unreserve_name(String::from("cOOng")).unwrap();
+      assert!(!motherspace.is_name_reserved(String::from("Coong")));
+
+      test::set_caller::<DefaultEnvironment>(accounts.bob);
+      assert!(motherspace.reserve_name(String::from("Bob")).is_err());
+      assert!(motherspace.unreserve_name(String::from("InSpace")).is_err());
+      assert!(motherspace.is_name_reserved(String::from("InSpace")));
+    }
+
+    fn resolve_dependencies(motherspace: &MotherSpace, plugin_ids: &[PluginId], installed: &Vec<PluginId>) -> Vec<PluginId> {
+      let mut ordered = Vec::new();
+      for plugin_id in plugin_ids {
+        motherspace.resolve_plugin_dependencies_impl(*plugin_id, installed, &mut ordered);
+      }
+
+      ordered
+    }
+
+    #[ink::test]
+    fn plugin_dependencies_are_resolved_first() {
+      let mut motherspace = new_motherspace();
+      motherspace.plugin_dependencies.insert([3; 4], &vec![[2; 4]]);
+      motherspace.plugin_dependencies.insert([2; 4], &vec![[1; 4]]);
+
+      assert_eq!(resolve_dependencies(&motherspace, &[[3; 4]], &Vec::new()), vec![[1; 4], [2; 4], [3; 4]]);
+      assert_eq!(resolve_dependencies(&motherspace, &[[1; 4]], &Vec::new()), vec![[1; 4]]);
+    }
+
+    #[ink::test]
+    fn shared_plugin_dependencies_are_resolved_once() {
+      let mut motherspace = new_motherspace();
+      motherspace.plugin_dependencies.insert([2; 4], &vec![[1; 4]]);
+      motherspace.plugin_dependencies.insert([3; 4], &vec![[1; 4], [2; 4]]);
+
+      assert_eq!(resolve_dependencies(&motherspace, &[[2; 4], [3; 4], [2; 4]], &Vec::new()), vec![[1; 4], [2; 4], [3; 4]]);
+    }
+
+    #[ink::test]
+    fn installed_plugin_dependencies_are_skipped() {
+      let mut motherspace = new_motherspace();
+      motherspace.plugin_dependencies.insert([3; 4], &vec![[1; 4], [2; 4]]);
+
+      assert_eq!(resolve_dependencies(&motherspace, &[[3; 4]], &vec![[1; 4]]), vec![[2; 4], [3; 4]]);
+      assert_eq!(resolve_dependencies(&motherspace, &[[3; 4]], &vec![[3; 4]]), Vec::<PluginId>::new());
+    }
+  }
+
+  #[cfg(all(test, feature = "e2e-tests"))]
+  mod e2e_tests {
+    use super::*;
+    use flipper_launcher::FlipperLauncherRef;
+    use ink_e2e::build_message;
+    use mock_launcher::MockLauncherRef;
+    use mock_psp22::MockPsp22Ref;
+    use openbrush::contracts::psp22::psp22_external::PSP22;
+    use openbrush::contracts::ownable::ownable_external::Ownable;
+    use openbrush::contracts::upgradeable::upgradeable_external::Upgradeable;
+    use shared::traits::plugin_launcher::PluginLauncher;
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+    type E2EClient = ink_e2e::Client<ink_e2e::PolkadotConfig, DefaultEnvironment>;
+
+    /// Deploy motherspace owned by alice, returns its id & the space code
+    async fn deploy_motherspace(client: &mut E2EClient) -> (AccountId, Hash) {
+      let space_code = client
+        .upload("space", &ink_e2e::alice(), None)
+        .await
+        .expect("upload space failed")
+        .code_hash;
+
+      let motherspace_id = client
+        .instantiate("motherspace", &ink_e2e::alice(), MotherSpaceRef::new(space_code, ink_e2e::account_id(ink_e2e::AccountKeyring::Alice)), 0, None)
+        .await
+        .expect("instantiate motherspace failed")
+        .account_id;
+
+      (motherspace_id, space_code)
+    }
+
+    async fn deploy_space(client: &mut E2EClient, motherspace_id: AccountId, name: &str, config: Option<SpaceConfig>) -> AccountId {
+      let info = SpaceInfo { name: String::from(name), desc: None, logo: None };
+      let deploy_new_space = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.deploy_new_space(info, config, None, None));
+
+      let (space_id, _) = client
+        .call(&ink_e2e::alice(), deploy_new_space, 0, None)
+        .await
+        .expect("deploy space failed")
+        .return_value()
+        .expect("deploy space returned error");
+
+      space_id
+    }
+
+    /// Register a flipper launcher with its checksum plugin id & install the plugin to the space
+    async fn install_flipper(client: &mut E2EClient, motherspace_id: AccountId, space_id: AccountId) -> PluginId {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let flipper_code = client
+        .upload("flipper", &ink_e2e::alice(), None)
+        .await
+        .expect("upload flipper failed")
+        .code_hash;
+
+      let launcher_id = client
+        .instantiate("flipper_launcher", &ink_e2e::alice(), FlipperLauncherRef::new(motherspace_id, alice, flipper_code), 0, None)
+        .await
+        .expect("instantiate flipper launcher failed")
+        .account_id;
+
+      let compute_plugin_id = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.compute_plugin_id(launcher_id));
+      let plugin_id = client.call_dry_run(&ink_e2e::alice(), &compute_plugin_id, 0, None).await.return_value();
+
+      let register_plugin_launcher = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.register_plugin_launcher(plugin_id, launcher_id, Vec::new()));
+      client.call(&ink_e2e::alice(), register_plugin_launcher, 0, None).await.expect("register plugin launcher failed");
+
+      let install_plugins = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.install_plugins(space_id, vec![plugin_id]));
+      client.call(&ink_e2e::alice(), install_plugins, 0, None).await.expect("install plugins failed");
+
+      plugin_id
+    }
+
+    /// Register a mock launcher reporting `launches_count` launches with its checksum plugin id
+    async fn register_mock_launcher(client: &mut E2EClient, motherspace_id: AccountId, launches_count: u32) -> PluginId {
+      let launcher_id = client
+        .instantiate("mock_launcher", &ink_e2e::alice(), MockLauncherRef::new(motherspace_id, launches_count), 0, None)
+        .await
+        .expect("instantiate mock launcher failed")
+        .account_id;
+
+      let compute_plugin_id = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.compute_plugin_id(launcher_id));
+      let plugin_id = client.call_dry_run(&ink_e2e::alice(), &compute_plugin_id, 0, None).await.return_value();
+
+      let register_plugin_launcher = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.register_plugin_launcher(plugin_id, launcher_id, Vec::new()));
+      client
+        .call(&ink_e2e::alice(), register_plugin_launcher, 0, None)
+        .await
+        .expect("register plugin launcher failed")
+        .return_value()
+        .expect("register plugin launcher returned error");
+
+      plugin_id
+    }
+
+    async fn spaces_by_owner(client: &mut E2EClient, motherspace_id: AccountId, who: AccountId, from: u32, per_page: u32) -> SpacesPage {
+      let spaces_by_owner = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.spaces_by_owner(who, from, per_page));
+
+      client.call_dry_run(&ink_e2e::alice(), &spaces_by_owner, 0, None).await.return_value()
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn deployed_space_is_owned_by_deployer(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+
+      let page = spaces_by_owner(&mut client, motherspace_id, alice, 0, 10).await;
+      assert_eq!(page.total, 1);
+      assert_eq!(page.items[0].0, space_id);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn space_ownership_changes_are_recorded(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+
+      let transfer_ownership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.transfer_ownership(Some(bob)));
+      client.call(&ink_e2e::alice(), transfer_ownership, 0, None).await.expect("transfer ownership failed");
+
+      assert_eq!(spaces_by_owner(&mut client, motherspace_id, alice, 0, 10).await.total, 0);
+      let page = spaces_by_owner(&mut client, motherspace_id, bob, 0, 10).await;
+      assert_eq!(page.total, 1);
+      assert_eq!(page.items[0].0, space_id);
+
+      let renounce_ownership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.renounce_ownership());
+      client.call(&ink_e2e::bob(), renounce_ownership, 0, None).await.expect("renounce ownership failed");
+
+      assert_eq!(spaces_by_owner(&mut client, motherspace_id, bob, 0, 10).await.total, 0);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn whitelisted_accounts_can_deploy_spaces(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+
+      let enable_deployer_whitelist = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.enable_deployer_whitelist());
+      client.call(&ink_e2e::alice(), enable_deployer_whitelist, 0, None).await.expect("enable deployer whitelist failed");
+      let add_to_whitelist = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.add_to_whitelist(bob));
+      client.call(&ink_e2e::alice(), add_to_whitelist, 0, None).await.expect("add to whitelist failed");
+
+      let deploy_new_space = |name: &str| {
+        let info = SpaceInfo { name: String::from(name), desc: None, logo: None };
+        build_message::<MotherSpaceRef>(motherspace_id.clone())
+          .call(move |motherspace| motherspace.deploy_new_space(info, None, None, None))
+      };
+
+      let result = client.call_dry_run(&ink_e2e::charlie(), &deploy_new_space("Charlie Space"), 0, None).await.return_value();
+      assert!(matches!(result, Err(MotherSpaceError::UnAuthorized)));
+
+      client.call(&ink_e2e::bob(), deploy_new_space("Bob Space"), 0, None).await.expect("whitelisted deploy failed");
+      // the owner is not in the whitelist but can still deploy
+      client.call(&ink_e2e::alice(), deploy_new_space("Alice Space"), 0, None).await.expect("owner deploy failed");
+
+      assert_eq!(spaces_by_owner(&mut client, motherspace_id, bob, 0, 10).await.total, 1);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn unreserved_name_can_be_used(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let deploy_new_space = || {
+        let info = SpaceInfo { name: String::from("InSpace"), desc: None, logo: None };
+        build_message::<MotherSpaceRef>(motherspace_id.clone())
+          .call(move |motherspace| motherspace.deploy_new_space(info, None, None, None))
+      };
+
+      let result = client.call_dry_run(&ink_e2e::alice(), &deploy_new_space(), 0, None).await.return_value();
+      assert!(matches!(result, Err(MotherSpaceError::Custom(message)) if message == "Space name is reserved"));
+
+      let unreserve_name = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.unreserve_name(String::from("INSPACE")));
+      client.call(&ink_e2e::alice(), unreserve_name, 0, None).await.expect("unreserve name failed");
+
+      client.call(&ink_e2e::alice(), deploy_new_space(), 0, None).await.expect("deploy space failed");
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn spaces_by_owner_is_paginated(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let first_space_id = deploy_space(&mut client, motherspace_id, "Space One", None).await;
+      let second_space_id = deploy_space(&mut client, motherspace_id, "Space Two", None).await;
+      let third_space_id = deploy_space(&mut client, motherspace_id, "Space Three", None).await;
+
+      let first_page = spaces_by_owner(&mut client, motherspace_id, alice, 0, 2).await;
+      assert_eq!(first_page.total, 3);
+      assert!(first_page.has_next_page);
+      assert_eq!(first_page.items.iter().map(|item| item.0).collect::<Vec<_>>(), vec![first_space_id, second_space_id]);
+
+      let second_page = spaces_by_owner(&mut client, motherspace_id, alice, 2, 2).await;
+      assert!(!second_page.has_next_page);
+      assert_eq!(second_page.items.iter().map(|item| item.0).collect::<Vec<_>>(), vec![third_space_id]);
+
+      Ok(())
+    }
+
+    async fn emergency_pause(client: &mut E2EClient, motherspace_id: AccountId) {
+      let emergency_pause = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.emergency_pause());
+
+      client.call(&ink_e2e::alice(), emergency_pause, 0, None).await.expect("emergency pause failed");
+    }
+
+    async fn members_count(client: &mut E2EClient, space_id: AccountId) -> u32 {
+      let members_count = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.members_count());
+
+      client.call_dry_run(&ink_e2e::alice(), &members_count, 0, None).await.return_value()
+    }
+
+    /// Pay to join a space, returns the welcome message
+    async fn join_space(client: &mut E2EClient, space_id: AccountId, signer: &ink_e2e::Keypair, client_nonce: u32) -> Option<String> {
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, client_nonce));
+
+      client
+        .call(signer, pay_to_join, 0, None)
+        .await
+        .expect("pay to join failed")
+        .return_value()
+        .expect("pay to join returned error")
+    }
+
+    async fn leave_space(client: &mut E2EClient, space_id: AccountId, signer: &ink_e2e::Keypair) {
+      let leave = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.leave());
+
+      client
+        .call(signer, leave, 0, None)
+        .await
+        .expect("leave failed")
+        .return_value()
+        .expect("leave returned error");
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn global_pause_blocks_space_writes(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+
+      emergency_pause(&mut client, motherspace_id).await;
+
+      let grant_membership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.grant_membership(bob, None));
+      let result = client.call_dry_run(&ink_e2e::alice(), &grant_membership, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "System is paused"));
+
+      // reads keep working
+      assert_eq!(members_count(&mut client, space_id).await, 1);
+
+      let emergency_unpause = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.emergency_unpause());
+      client.call(&ink_e2e::alice(), emergency_unpause, 0, None).await.expect("emergency unpause failed");
+
+      client.call(&ink_e2e::alice(), grant_membership, 0, None).await.expect("grant membership failed");
+      assert_eq!(members_count(&mut client, space_id).await, 2);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn space_falls_back_to_pause_cache(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, space_code) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+
+      emergency_pause(&mut client, motherspace_id).await;
+
+      let refresh_pause_cache = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.refresh_pause_cache());
+      let paused = client
+        .call(&ink_e2e::alice(), refresh_pause_cache, 0, None)
+        .await
+        .expect("refresh pause cache failed")
+        .return_value();
+      assert!(matches!(paused, Ok(true)));
+
+      // motherspace stops responding to pause queries once its code is swapped for one without them
+      let set_code_hash = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.set_code_hash(space_code));
+      client.call(&ink_e2e::alice(), set_code_hash, 0, None).await.expect("set code hash failed");
+
+      let grant_membership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.grant_membership(bob, None));
+      let result = client.call_dry_run(&ink_e2e::alice(), &grant_membership, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "System is paused"));
+
+      assert_eq!(members_count(&mut client, space_id).await, 1);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn members_count_follows_join_leave_rejoin(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+      let ever_joined_count = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.ever_joined_count());
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      join_space(&mut client, space_id, &ink_e2e::charlie(), 1).await;
+      assert_eq!(members_count(&mut client, space_id).await, 3);
+
+      leave_space(&mut client, space_id, &ink_e2e::bob()).await;
+      assert_eq!(members_count(&mut client, space_id).await, 2);
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 2).await;
+      assert_eq!(members_count(&mut client, space_id).await, 3);
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &ever_joined_count, 0, None).await.return_value(), 3);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn client_nonce_cannot_be_reused(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      const PRICE: Balance = 1_000_000_000;
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let config = SpaceConfig { pricing: Pricing::OneTimePaid { price: PRICE }, ..Default::default() };
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(config)).await;
+
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, 7));
+      client.call(&ink_e2e::bob(), pay_to_join, PRICE, None).await.expect("pay to join failed");
+
+      let next_nonce_for = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.next_nonce_for(bob));
+      assert_eq!(client.call_dry_run(&ink_e2e::bob(), &next_nonce_for, 0, None).await.return_value(), 8);
+
+      let leave = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.leave());
+      client.call(&ink_e2e::bob(), leave, 0, None).await.expect("leave failed");
+
+      // rejoining with a used nonce is rejected & the payment is not kept
+      let space_balance = client.balance(space_id.clone()).await.expect("space balance failed");
+      let reused_nonce = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, 7));
+      let result = client.call_dry_run(&ink_e2e::bob(), &reused_nonce, PRICE, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Nonce already used"));
+      assert!(client.call(&ink_e2e::bob(), reused_nonce, PRICE, None).await.is_err());
+      assert_eq!(client.balance(space_id.clone()).await.expect("space balance failed"), space_balance);
+
+      let fresh_nonce = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, 8));
+      client.call(&ink_e2e::bob(), fresh_nonce, PRICE, None).await.expect("pay to join with a fresh nonce failed");
+
+      let is_active_member = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.is_active_member(bob));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &is_active_member, 0, None).await.return_value());
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn nonce_rejected_request_is_not_refundable(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      const PRICE: Balance = 1_000_000_000;
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let config = SpaceConfig {
+        registration: RegistrationType::RequestToJoin,
+        pricing: Pricing::OneTimePaid { price: PRICE },
+        ..Default::default()
+      };
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(config)).await;
+
+      let register_membership = || build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.register_membership(None, None, None, 1));
+      let cancel_request = || build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.cancel_request());
+
+      client.call(&ink_e2e::bob(), register_membership(), PRICE, None).await.expect("register membership failed");
+      let space_balance = client.balance(space_id.clone()).await.expect("space balance failed");
+      client.call(&ink_e2e::bob(), cancel_request(), 0, None).await.expect("cancel request failed");
+      assert_eq!(client.balance(space_id.clone()).await.expect("space balance failed"), space_balance - PRICE);
+
+      // the reused nonce is rejected before any request is recorded, so there is nothing to refund
+      let result = client.call_dry_run(&ink_e2e::bob(), &register_membership(), PRICE, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Nonce already used"));
+      assert!(client.call(&ink_e2e::bob(), register_membership(), PRICE, None).await.is_err());
+
+      let pending_requests_count = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pending_requests_count());
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &pending_requests_count, 0, None).await.return_value(), 0);
+
+      let result = client.call_dry_run(&ink_e2e::bob(), &cancel_request(), 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Request Not Found"));
+      assert_eq!(client.balance(space_id.clone()).await.expect("space balance failed"), space_balance - PRICE);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn third_party_payment_requires_authorization(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+      let dave = ink_e2e::account_id(ink_e2e::AccountKeyring::Dave);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+
+      // authorization is required by default
+      let pay_for_bob = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(Some(bob), None, 1));
+      let result = client.call_dry_run(&ink_e2e::charlie(), &pay_for_bob, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::UnAuthorized)));
+
+      let authorize_payer = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.authorize_payer(charlie));
+      client.call(&ink_e2e::bob(), authorize_payer, 0, None).await.expect("authorize payer failed");
+      client.call(&ink_e2e::charlie(), pay_for_bob, 0, None).await.expect("pay for bob failed");
+
+      // revoked authorizations are no longer accepted
+      let authorize_payer = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.authorize_payer(charlie));
+      client.call(&ink_e2e::dave(), authorize_payer, 0, None).await.expect("authorize payer failed");
+      let revoke_payer_authorization = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.revoke_payer_authorization());
+      client.call(&ink_e2e::dave(), revoke_payer_authorization, 0, None).await.expect("revoke payer authorization failed");
+
+      let pay_for_dave = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(Some(dave), None, 2));
+      let result = client.call_dry_run(&ink_e2e::charlie(), &pay_for_dave, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::UnAuthorized)));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn third_party_payment_can_skip_authorization(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let config = SpaceConfig { allow_third_party_payment: true, ..Default::default() };
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(config)).await;
+
+      let pay_for_bob = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(Some(bob), None, 1));
+      client.call(&ink_e2e::charlie(), pay_for_bob, 0, None).await.expect("pay for bob failed");
+
+      let is_active_member = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.is_active_member(bob));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &is_active_member, 0, None).await.return_value());
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn pending_requests_are_capped(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let config = SpaceConfig { registration: RegistrationType::RequestToJoin, max_pending_requests: Some(2), ..Default::default() };
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(config)).await;
+
+      // fill exactly `max_pending_requests`
+      for signer in [ink_e2e::bob(), ink_e2e::charlie()] {
+        let register_membership = build_message::<SpaceRef>(space_id.clone())
+          .call(|space| space.register_membership(None, None, None, 1));
+        client.call(&signer, register_membership, 0, None).await.expect("register membership failed");
+      }
+
+      let pending_requests_count = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pending_requests_count());
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &pending_requests_count, 0, None).await.return_value(), 2);
+
+      let register_membership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.register_membership(None, None, None, 1));
+      let result = client.call_dry_run(&ink_e2e::dave(), &register_membership, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::PendingRequestsAtCapacity)));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml")]
+    async fn questionnaire_answers_are_validated_and_stored(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let config = SpaceConfig { registration: RegistrationType::RequestToJoin, ..Default::default() };
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(config)).await;
+
+      let register_membership = |answers: Option<Vec<String>>| build_message::<SpaceRef>(space_id.clone())
+        .call(move |space| space.register_membership(None, answers, None, 1));
+      let request_answers = |request_id: u32| build_message::<SpaceRef>(space_id.clone())
+        .call(move |space| space.get_request_with_answers(request_id));
+
+      // no answers are required when there is no questionnaire
+      client.call(&ink_e2e::bob(), register_membership(None), 0, None).await.expect("register membership failed");
+      let result = client.call_dry_run(&ink_e2e::alice(), &request_answers(1), 0, None).await.return_value();
+      assert_eq!(result.expect("request not found").1, Vec::<String>::new());
+
+      let questions = vec![String::from("Who are you?"), String::from("Why do you want to join?")];
+      let set_join_questionnaire = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.set_join_questionnaire(Some(questions)));
+      client.call(&ink_e2e::alice(), set_join_questionnaire, 0, None).await.expect("set join questionnaire failed");
+
+      // number of answers must match number of questions
+      for answers in [None, Some(vec![String::from("Charlie")]), Some(vec![String::new(); 3])] {
+        let result = client.call_dry_run(&ink_e2e::charlie(), &register_membership(answers), 0, None).await.return_value();
+        assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Number of answers must match number of questions"));
+      }
+
+      let answers = vec![String::from("Charlie"), String::from("To build things")];
+      client.call(&ink_e2e::charlie(), register_membership(Some(answers.clone())), 0, None).await.expect("register membership failed");
+      let result = client.call_dry_run(&ink_e2e::alice(), &request_answers(2), 0, None).await.return_value();
+      assert_eq!(result.expect("request not found").1, answers);
+
+      // answers are only readable by the owner
+      let result = client.call_dry_run(&ink_e2e::charlie(), &request_answers(2), 0, None).await.return_value();
+      assert!(result.is_err());
+
+      Ok(())
+    }
+
+    const TOKEN_PRICE: Balance = 1_000;
+
+    /// Deploy a mock PSP22 token with the whole supply minted to bob
+    async fn deploy_token(client: &mut E2EClient) -> AccountId {
+      client
+        .instantiate("mock_psp22", &ink_e2e::bob(), MockPsp22Ref::new(1_000_000), 0, None)
+        .await
+        .expect("instantiate mock psp22 failed")
+        .account_id
+    }
+
+    fn token_config(token_id: AccountId, registration: RegistrationType) -> SpaceConfig {
+      SpaceConfig {
+        registration,
+        pricing: Pricing::SubscriptionWithToken { token_contract: token_id, price: TOKEN_PRICE, duration_secs: 86_400 },
+        ..Default::default()
+      }
+    }
+
+    async fn approve_token(client: &mut E2EClient, token_id: AccountId, spender: AccountId, amount: Balance) {
+      let approve = build_message::<MockPsp22Ref>(token_id.clone())
+        .call(|token| token.approve(spender, amount));
+      client
+        .call(&ink_e2e::bob(), approve, 0, None)
+        .await
+        .expect("approve failed")
+        .return_value()
+        .expect("approve returned error");
+    }
+
+    async fn token_balance(client: &mut E2EClient, token_id: AccountId, who: AccountId) -> Balance {
+      let balance_of = build_message::<MockPsp22Ref>(token_id.clone())
+        .call(|token| token.balance_of(who));
+
+      client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await.return_value()
+    }
+
+    async fn token_fees_balance(client: &mut E2EClient, space_id: AccountId, token_id: AccountId) -> Balance {
+      let token_fees_balance = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.token_fees_balance(token_id));
+
+      client.call_dry_run(&ink_e2e::alice(), &token_fees_balance, 0, None).await.return_value()
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../mocks/psp22/Cargo.toml")]
+    async fn token_subscription_with_sufficient_allowance(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let token_id = deploy_token(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(token_config(token_id, RegistrationType::PayToJoin))).await;
+
+      approve_token(&mut client, token_id, space_id, TOKEN_PRICE).await;
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, 1));
+      client.call(&ink_e2e::bob(), pay_to_join, 0, None).await.expect("pay to join failed");
+
+      let is_active_member = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.is_active_member(bob));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &is_active_member, 0, None).await.return_value());
+      assert_eq!(token_balance(&mut client, token_id, space_id).await, TOKEN_PRICE);
+      assert_eq!(token_balance(&mut client, token_id, bob).await, 1_000_000 - TOKEN_PRICE);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../mocks/psp22/Cargo.toml")]
+    async fn token_subscription_with_insufficient_allowance(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let token_id = deploy_token(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(token_config(token_id, RegistrationType::PayToJoin))).await;
+
+      approve_token(&mut client, token_id, space_id, TOKEN_PRICE - 1).await;
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, 1));
+      let result = client.call_dry_run(&ink_e2e::bob(), &pay_to_join, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::InsufficientPayment)));
+      assert_eq!(token_balance(&mut client, token_id, bob).await, 1_000_000);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../mocks/psp22/Cargo.toml")]
+    async fn token_fees_can_be_withdrawn(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let token_id = deploy_token(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(token_config(token_id, RegistrationType::PayToJoin))).await;
+
+      approve_token(&mut client, token_id, space_id, TOKEN_PRICE).await;
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, 1));
+      client.call(&ink_e2e::bob(), pay_to_join, 0, None).await.expect("pay to join failed");
+      assert_eq!(token_fees_balance(&mut client, space_id, token_id).await, TOKEN_PRICE);
+
+      let withdraw_too_much = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.withdraw_token_fees(token_id, TOKEN_PRICE + 1, charlie));
+      let result = client.call_dry_run(&ink_e2e::alice(), &withdraw_too_much, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Insufficient token fees"));
+
+      // only owner can withdraw
+      let withdraw_token_fees = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.withdraw_token_fees(token_id, TOKEN_PRICE, charlie));
+      let result = client.call_dry_run(&ink_e2e::bob(), &withdraw_token_fees, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::OwnableError(_))));
+
+      client.call(&ink_e2e::alice(), withdraw_token_fees, 0, None).await.expect("withdraw token fees failed");
+      assert_eq!(token_balance(&mut client, token_id, charlie).await, TOKEN_PRICE);
+      assert_eq!(token_fees_balance(&mut client, space_id, token_id).await, 0);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../mocks/psp22/Cargo.toml")]
+    async fn pending_token_payments_are_not_withdrawable(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let token_id = deploy_token(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(token_config(token_id, RegistrationType::RequestToJoin))).await;
+
+      approve_token(&mut client, token_id, space_id, TOKEN_PRICE).await;
+      let register_membership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.register_membership(None, None, None, 1));
+      client.call(&ink_e2e::bob(), register_membership, 0, None).await.expect("register membership failed");
+
+      assert_eq!(token_balance(&mut client, token_id, space_id).await, TOKEN_PRICE);
+      assert_eq!(token_fees_balance(&mut client, space_id, token_id).await, 0);
+      let withdraw_token_fees = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.withdraw_token_fees(token_id, 1, bob));
+      let result = client.call_dry_run(&ink_e2e::alice(), &withdraw_token_fees, 0, None).await.return_value();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Insufficient token fees"));
+
+      // the escrowed tokens are still there to refund
+      let batch_cancel_requests = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.batch_cancel_requests(vec![1]));
+      client.call(&ink_e2e::alice(), batch_cancel_requests, 0, None).await.expect("batch cancel requests failed");
+      assert_eq!(token_balance(&mut client, token_id, bob).await, 1_000_000);
+      assert_eq!(token_balance(&mut client, token_id, space_id).await, 0);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../mocks/psp22/Cargo.toml")]
+    async fn failed_token_refund_keeps_native_payment(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      const NATIVE_PAID: Balance = 1_000_000_000;
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let token_id = deploy_token(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", Some(token_config(token_id, RegistrationType::RequestToJoin))).await;
+
+      approve_token(&mut client, token_id, space_id, TOKEN_PRICE).await;
+      let register_membership = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.register_membership(None, None, None, 1));
+      client.call(&ink_e2e::bob(), register_membership, NATIVE_PAID, None).await.expect("register membership failed");
+
+      let set_transfers_disabled = build_message::<MockPsp22Ref>(token_id.clone())
+        .call(|token| token.set_transfers_disabled(true));
+      client.call(&ink_e2e::bob(), set_transfers_disabled, 0, None).await.expect("disable transfers failed");
+
+      let pending_requests_count = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pending_requests_count());
+      let space_balance = client.balance(space_id.clone()).await.expect("space balance failed");
+
+      // neither payment is refunded & the request stays pending
+      let batch_cancel_requests = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.batch_cancel_requests(vec![1]));
+      client.call(&ink_e2e::alice(), batch_cancel_requests, 0, None).await.expect("batch cancel requests failed");
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &pending_requests_count, 0, None).await.return_value(), 1);
+      assert_eq!(client.balance(space_id.clone()).await.expect("space balance failed"), space_balance);
+      assert_eq!(token_balance(&mut client, token_id, space_id).await, TOKEN_PRICE);
+
+      let set_transfers_enabled = build_message::<MockPsp22Ref>(token_id.clone())
+        .call(|token| token.set_transfers_disabled(false));
+      client.call(&ink_e2e::bob(), set_transfers_enabled, 0, None).await.expect("enable transfers failed");
+
+      // the retry refunds both payments exactly once
+      let batch_cancel_requests = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.batch_cancel_requests(vec![1]));
+      client.call(&ink_e2e::alice(), batch_cancel_requests, 0, None).await.expect("batch cancel requests failed");
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &pending_requests_count, 0, None).await.return_value(), 0);
+      assert_eq!(client.balance(space_id.clone()).await.expect("space balance failed"), space_balance - NATIVE_PAID);
+      assert_eq!(token_balance(&mut client, token_id, bob).await, 1_000_000);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../plugins/flipper/Cargo.toml ../plugins/flipper/launcher/Cargo.toml")]
+    async fn is_plugin_installed_matches_plugin_ids(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let space_id = deploy_space(&mut client, motherspace_id, "My Space", None).await;
+
+      let is_plugin_installed = |plugin_id: PluginId| build_message::<SpaceRef>(space_id.clone())
+        .call(move |space| space.is_plugin_installed(plugin_id));
+      let plugin_ids = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.plugin_ids());
+
+      let unknown_plugin_id: PluginId = [1, 2, 3, 4];
+      assert!(!client.call_dry_run(&ink_e2e::alice(), &is_plugin_installed(unknown_plugin_id), 0, None).await.return_value());
+
+      let plugin_id = install_flipper(&mut client, motherspace_id, space_id).await;
+      assert!(client.call_dry_run(&ink_e2e::alice(), &is_plugin_installed(plugin_id), 0, None).await.return_value());
+      assert!(!client.call_dry_run(&ink_e2e::alice(), &is_plugin_installed(unknown_plugin_id), 0, None).await.return_value());
+
+      let installed_ids = client.call_dry_run(&ink_e2e::alice(), &plugin_ids, 0, None).await.return_value();
+      assert_eq!(installed_ids, vec![plugin_id]);
+      for id in installed_ids {
+        assert!(client.call_dry_run(&ink_e2e::alice(), &is_plugin_installed(id), 0, None).await.return_value());
+      }
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../plugins/flipper/Cargo.toml ../plugins/flipper/launcher/Cargo.toml")]
+    async fn plugin_launches_are_counted_per_space(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let flipper_code = client
+        .upload("flipper", &ink_e2e::alice(), None)
+        .await
+        .expect("upload flipper failed")
+        .code_hash;
+
+      // alice acts as motherspace of the launcher
+      let launcher_id = client
+        .instantiate("flipper_launcher", &ink_e2e::alice(), FlipperLauncherRef::new(alice, alice, flipper_code), 0, None)
+        .await
+        .expect("instantiate flipper launcher failed")
+        .account_id;
+
+      let launch = |space_id: AccountId| build_message::<FlipperLauncherRef>(launcher_id.clone())
+        .call(move |launcher| launcher.launch(space_id));
+      let installs_for_space = |space_id: AccountId| build_message::<FlipperLauncherRef>(launcher_id.clone())
+        .call(move |launcher| launcher.installs_for_space(space_id));
+      let spaces_using_plugin_count = build_message::<FlipperLauncherRef>(launcher_id.clone())
+        .call(|launcher| launcher.spaces_using_plugin_count());
+
+      let first_space_id = AccountId::from([0x01; 32]);
+      let second_space_id = AccountId::from([0x02; 32]);
+
+      client.call(&ink_e2e::alice(), launch(first_space_id), 0, None).await.expect("launch failed");
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &installs_for_space(first_space_id), 0, None).await.return_value(), 1);
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &spaces_using_plugin_count, 0, None).await.return_value(), 1);
+
+      // re-installing from the same space is counted for the space, not as a new space
+      client.call(&ink_e2e::alice(), launch(first_space_id), 0, None).await.expect("launch failed");
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &installs_for_space(first_space_id), 0, None).await.return_value(), 2);
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &spaces_using_plugin_count, 0, None).await.return_value(), 1);
+
+      client.call(&ink_e2e::alice(), launch(second_space_id), 0, None).await.expect("launch failed");
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &installs_for_space(first_space_id), 0, None).await.return_value(), 2);
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &installs_for_space(second_space_id), 0, None).await.return_value(), 1);
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &installs_for_space(AccountId::from([0x03; 32])), 0, None).await.return_value(), 0);
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &spaces_using_plugin_count, 0, None).await.return_value(), 2);
+
+      let launches_count = build_message::<FlipperLauncherRef>(launcher_id.clone())
+        .call(|launcher| launcher.launches_count());
+      assert_eq!(client.call_dry_run(&ink_e2e::alice(), &launches_count, 0, None).await.return_value(), 3);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../plugins/flipper/Cargo.toml ../plugins/flipper/launcher/Cargo.toml")]
+    async fn plugin_id_checksum_can_be_disabled(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+
+      let flipper_code = client
+        .upload("flipper", &ink_e2e::alice(), None)
+        .await
+        .expect("upload flipper failed")
+        .code_hash;
+      let launcher_id = client
+        .instantiate("flipper_launcher", &ink_e2e::alice(), FlipperLauncherRef::new(motherspace_id, alice, flipper_code), 0, None)
+        .await
+        .expect("instantiate flipper launcher failed")
+        .account_id;
+
+      let hand_picked_id: PluginId = *b"FLIP";
+      let register_plugin_launcher = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.register_plugin_launcher(hand_picked_id, launcher_id, Vec::new()));
+      let result = client.call_dry_run(&ink_e2e::alice(), &register_plugin_launcher, 0, None).await.return_value();
+      assert!(result.is_err());
+
+      let set_strict_plugin_id_enforcement = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.set_strict_plugin_id_enforcement(false));
+      client.call(&ink_e2e::alice(), set_strict_plugin_id_enforcement, 0, None).await.expect("disable enforcement failed");
+
+      client.call(&ink_e2e::alice(), register_plugin_launcher, 0, None).await.expect("register plugin launcher failed");
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../mocks/launcher/Cargo.toml")]
+    async fn plugin_ecosystem_stats_are_paginated(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let first_plugin_id = register_mock_launcher(&mut client, motherspace_id, 5).await;
+      let second_plugin_id = register_mock_launcher(&mut client, motherspace_id, 1).await;
+      let third_plugin_id = register_mock_launcher(&mut client, motherspace_id, 3).await;
+
+      let plugin_ecosystem_stats = |from: u32, per_page: u32| build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(move |motherspace| motherspace.plugin_ecosystem_stats(from, per_page));
+
+      let first_page = client.call_dry_run(&ink_e2e::alice(), &plugin_ecosystem_stats(0, 2), 0, None).await.return_value();
+      assert_eq!(first_page.iter().map(|stats| stats.plugin_id).collect::<Vec<_>>(), vec![first_plugin_id, second_plugin_id]);
+      assert_eq!(first_page.iter().map(|stats| stats.total_installations).collect::<Vec<_>>(), vec![5, 1]);
+      assert!(first_page.iter().all(|stats| stats.latest_version == 1 && stats.spaces_using == 0));
+
+      let second_page = client.call_dry_run(&ink_e2e::alice(), &plugin_ecosystem_stats(2, 2), 0, None).await.return_value();
+      assert_eq!(second_page.iter().map(|stats| (stats.plugin_id, stats.total_installations)).collect::<Vec<_>>(), vec![(third_plugin_id, 3)]);
+
+      let out_of_range = client.call_dry_run(&ink_e2e::alice(), &plugin_ecosystem_stats(3, 2), 0, None).await.return_value();
+      assert!(out_of_range.is_empty());
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../space/Cargo.toml ../plugins/flipper/Cargo.toml ../plugins/flipper/launcher/Cargo.toml ../mocks/launcher/Cargo.toml")]
+    async fn most_installed_plugins_are_ranked_by_installations(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let (motherspace_id, _) = deploy_motherspace(&mut client).await;
+      let first_space_id = deploy_space(&mut client, motherspace_id, "Space One", None).await;
+      let second_space_id = deploy_space(&mut client, motherspace_id, "Space Two", None).await;
+
+      // launches reported by a launcher are not counted, only installations via motherspace are
+      let unused_plugin_id = register_mock_launcher(&mut client, motherspace_id, 100).await;
+      let once_plugin_id = install_flipper(&mut client, motherspace_id, first_space_id).await;
+      let twice_plugin_id = install_flipper(&mut client, motherspace_id, first_space_id).await;
+
+      let install_plugins = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.install_plugins(second_space_id, vec![twice_plugin_id]));
+      client.call(&ink_e2e::alice(), install_plugins, 0, None).await.expect("install plugins failed");
+
+      let most_installed_plugins = |top_n: u32| build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(move |motherspace| motherspace.most_installed_plugins(top_n));
+
+      let ranking = client.call_dry_run(&ink_e2e::alice(), &most_installed_plugins(10), 0, None).await.return_value();
+      assert_eq!(ranking, vec![(twice_plugin_id, 2), (once_plugin_id, 1), (unused_plugin_id, 0)]);
+
+      let top = client.call_dry_run(&ink_e2e::alice(), &most_installed_plugins(1), 0, None).await.return_value();
+      assert_eq!(top, vec![(twice_plugin_id, 2)]);
+
+      Ok(())
+    }
+  }
 }
@@ -1,5 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use flipper_launcher::{FlipperLauncherRef};
+
 #[openbrush::implementation(Ownable, Upgradeable)]
 #[openbrush::contract]
 mod flipper_launcher {
@@ -20,11 +22,12 @@ mod flipper_launcher {
   }
 
   impl plugin_launcher::Instantiator for FlipperLauncher {
-    fn _initiate_new_plugin(&self, space_id: AccountId, launcher_id: AccountId, salt: &[u8]) -> Result<AccountId, LauncherError> {
+    fn _initiate_new_plugin(&self, space_id: AccountId, launcher_id: AccountId, salt: &[u8], init_data: &[u8]) -> Result<AccountId, LauncherError> {
       let input =
         ExecutionInput::new(Selector::new(ink::selector_bytes!("new")))
           .push_arg(space_id)
-          .push_arg(launcher_id);
+          .push_arg(launcher_id)
+          .push_arg(init_data);
 
       let new_contract: FlipperRef = build_create::<FlipperRef>()
         .code_hash(self.latest_plugin_code())
@@ -4,6 +4,7 @@ pub use flipper::{FlipperRef};
 
 #[openbrush::contract]
 mod flipper {
+  use ink::prelude::vec::Vec;
   use openbrush::{modifiers, traits::Storage};
   use shared::traits::codehash::*;
   use shared::traits::plugin_base::*;
@@ -22,7 +23,7 @@ mod flipper {
 
   impl Flipper {
     #[ink(constructor)]
-    pub fn new(space_id: AccountId, launcher_id: AccountId) -> Self {
+    pub fn new(space_id: AccountId, launcher_id: AccountId, _init_data: Vec<u8>) -> Self {
       let mut one = Self::default();
       plugin_base::PluginBase::_init(&mut one, space_id, launcher_id);
 
@@ -7,6 +7,7 @@ mod polls {
   use ink::prelude::{vec::Vec};
   use ink::storage::{Mapping, Lazy};
   use openbrush::{modifiers, traits::{Storage, String}};
+  use shared::ensure;
   use shared::traits::codehash::*;
   use shared::traits::plugin_base::*;
 
@@ -15,6 +16,13 @@ mod polls {
   type PollId = u32;
   type OptionIndex = u32;
 
+  const MIN_TITLE_LEN: usize = 3;
+  const MAX_TITLE_LEN: usize = 100;
+  const MAX_DESC_LEN: usize = 500;
+  const MIN_OPTIONS: usize = 2;
+  const MAX_OPTIONS: usize = 10;
+  const MAX_OPTION_LEN: usize = 100;
+
   #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Clone)]
   #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
   pub enum PollError {
@@ -24,6 +32,12 @@ mod polls {
     InvalidOptionIndex,
     VoteNotFound,
     PollExpired,
+    MandatoryVotePending,
+    PollNotFinalized,
+    RewardsAlreadyDistributed,
+    RewardsNotDistributed,
+    NoRewardToClaim,
+    CannotTransferReward,
   }
 
   impl From<PluginError> for PollError {
@@ -42,6 +56,17 @@ mod polls {
     created_at: Timestamp,
     updated_at: Option<Timestamp>,
     expired_at: Option<Timestamp>,
+    mandatory: bool,
+    reward_pool: Balance,
+  }
+
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub struct PollReward {
+    /// None -> no winner (no votes or a tie), the reward pool is refunded to the poll author
+    winning_option: Option<OptionIndex>,
+    /// amount each voter of the winning option can claim
+    share: Balance,
   }
 
   #[derive(Clone, Debug, scale::Decode, scale::Encode)]
@@ -63,6 +88,17 @@ mod polls {
 
     votes_voters: Mapping<(PollId, AccountId), OptionIndex>,
     votes_counters: Mapping<(PollId, OptionIndex), u32>,
+    // total votes of a poll, polls voted before this existed fall back to summing `votes_counters`
+    cached_total_votes: Mapping<PollId, u32>,
+    // set once the first vote of a poll is cast
+    has_votes: Mapping<PollId, bool>,
+
+    // open mandatory polls, members must vote on these before posting or voting elsewhere
+    mandatory_poll_ids: Lazy<Vec<PollId>>,
+
+    // rewards
+    poll_rewards: Mapping<PollId, PollReward>,
+    claimed_rewards: Mapping<(PollId, AccountId), Balance>,
   }
 
   impl CodeHash for Polls {}
@@ -71,18 +107,22 @@ mod polls {
 
   impl Polls {
     #[ink(constructor)]
-    pub fn new(space_id: AccountId, launcher_id: AccountId) -> Self {
+    pub fn new(space_id: AccountId, launcher_id: AccountId, _init_data: Vec<u8>) -> Self {
       let mut one = Self::default();
       plugin_base::PluginBase::_init(&mut one, space_id, launcher_id);
 
       one
     }
 
-    /// New poll
-    #[ink(message)]
+    /// New poll, transferred value is used as the reward pool for voters of the winning option
+    #[ink(message, payable)]
     #[modifiers(only_space_owner)] // For now, only space owner can create poll
     pub fn new_poll(&mut self, title: String, desc: Option<String>,
-                    options: Vec<String>, expired_at: Option<Timestamp>) -> PollResult<PollId> {
+                    options: Vec<String>, expired_at: Option<Timestamp>, mandatory: bool) -> PollResult<PollId> {
+      Self::validate_title(&title)?;
+      Self::validate_desc(&desc)?;
+      Self::validate_options(&options)?;
+
       let new_poll_id = self.polls_nonce.get_or_default();
       let next_poll_id = new_poll_id.checked_add(1).expect("Exceeding number of polls!");
 
@@ -94,12 +134,20 @@ mod polls {
         created_at: self.env().block_timestamp(),
         updated_at: None,
         expired_at,
+        mandatory,
+        reward_pool: self.env().transferred_value(),
       };
 
       self.polls.insert(new_poll_id, &new_poll);
       self.polls_nonce.set(&next_poll_id);
 
-      Ok(0)
+      if mandatory {
+        let mut mandatory_poll_ids = self.mandatory_poll_ids.get_or_default();
+        mandatory_poll_ids.push(new_poll_id);
+        self.mandatory_poll_ids.set(&mandatory_poll_ids);
+      }
+
+      Ok(new_poll_id)
     }
     /// Update poll
     #[ink(message)]
@@ -107,18 +155,30 @@ mod polls {
     pub fn update_poll(&mut self, poll_id: PollId, title: Option<String>, desc: Option<String>,
                        options: Option<Vec<String>>, expired_at: Option<Timestamp>) -> PollResult<()> {
       let mut poll = self.polls.get(poll_id).ok_or(PollError::PollNotFound)?;
+      ensure!(!self.poll_rewards.contains(poll_id), PollError::RewardsAlreadyDistributed);
 
       if let Some(value) = title {
+        Self::validate_title(&value)?;
         poll.title = value;
       }
 
+      Self::validate_desc(&desc)?;
       poll.desc = desc;
 
       if let Some(values) = options {
+        Self::validate_options(&values)?;
         if values.len() < poll.options.len() {
           return Err(PollError::Custom(String::from("Cannot remove option")));
         }
 
+        // existing options keep their indexes once votes are cast, only appending is allowed
+        if self.has_votes(poll_id) {
+          ensure!(
+            values[..poll.options.len()] == poll.options[..],
+            PollError::Custom(String::from("Cannot reorder options after voting started"))
+          );
+        }
+
         poll.options = values;
       }
 
@@ -127,9 +187,132 @@ mod polls {
 
       self.polls.insert(poll_id, &poll);
 
+      // a closed mandatory poll reopened via its expiry has to be voted on again
+      if poll.mandatory && self.ensure_active_poll(poll_id).is_ok() {
+        let mut mandatory_poll_ids = self.mandatory_poll_ids.get_or_default();
+        if !mandatory_poll_ids.contains(&poll_id) {
+          mandatory_poll_ids.push(poll_id);
+          self.mandatory_poll_ids.set(&mandatory_poll_ids);
+        }
+      }
+
+      Ok(())
+    }
+
+    /// Close poll, no more votes can be cast after this
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn close_poll(&mut self, poll_id: PollId) -> PollResult<()> {
+      let mut poll = self.ensure_active_poll(poll_id)?;
+
+      let now = self.env().block_timestamp();
+      poll.expired_at = Some(now);
+      poll.updated_at = Some(now);
+      self.polls.insert(poll_id, &poll);
+
+      if poll.mandatory {
+        let mut mandatory_poll_ids = self.mandatory_poll_ids.get_or_default();
+        mandatory_poll_ids.retain(|&id| id != poll_id);
+        self.mandatory_poll_ids.set(&mandatory_poll_ids);
+      }
+
+      Ok(())
+    }
+
+    /// Check if an account still has to vote on any open mandatory poll
+    #[ink(message)]
+    pub fn has_pending_mandatory_vote(&self, who: AccountId) -> bool {
+      !self.list_pending_mandatory_polls(who).is_empty()
+    }
+
+    /// List open mandatory polls that an account has not voted on yet
+    #[ink(message)]
+    pub fn list_pending_mandatory_polls(&self, who: AccountId) -> Vec<PollId> {
+      self.mandatory_poll_ids.get_or_default()
+        .into_iter()
+        .filter(|&id| self.ensure_active_poll(id).is_ok() && !self.votes_voters.contains((id, who)))
+        .collect()
+    }
+
+    /// Distribute the reward pool of a finalized poll to voters of the winning option
+    /// Anyone can call this, voters then claim their share via `claim_poll_reward`
+    #[ink(message)]
+    pub fn distribute_poll_rewards(&mut self, poll_id: PollId) -> PollResult<()> {
+      let poll = self.polls.get(poll_id).ok_or(PollError::PollNotFound)?;
+      ensure!(self.ensure_active_poll(poll_id).is_err(), PollError::PollNotFinalized);
+      ensure!(!self.poll_rewards.contains(poll_id), PollError::RewardsAlreadyDistributed);
+
+      let mut winning_option: Option<OptionIndex> = None;
+      let mut winning_votes: u32 = 0;
+      for index in 0..(poll.options.len()) {
+        let option_index = index as u32;
+        let votes = self.votes_counters.get((poll_id, option_index)).unwrap_or_default();
+
+        if votes > winning_votes {
+          winning_option = Some(option_index);
+          winning_votes = votes;
+        } else if votes == winning_votes {
+          // tie
+          winning_option = None;
+        }
+      }
+
+      let share = match winning_option {
+        Some(_) => poll.reward_pool.checked_div(winning_votes as Balance).unwrap_or_default(),
+        None => 0,
+      };
+
+      // Refund the remaining to the poll author
+      let distributed = share.saturating_mul(winning_votes as Balance);
+      let remaining = poll.reward_pool.saturating_sub(distributed);
+      if remaining > 0 && self.env().transfer(poll.author, remaining).is_err() {
+        return Err(PollError::CannotTransferReward);
+      }
+
+      self.poll_rewards.insert(poll_id, &PollReward { winning_option, share });
+
+      Ok(())
+    }
+
+    /// Claim reward share of a poll
+    #[ink(message)]
+    pub fn claim_poll_reward(&mut self, poll_id: PollId) -> PollResult<()> {
+      let voter = self.env().caller();
+      let reward = self.pending_reward(poll_id, voter)?;
+      ensure!(reward > 0, PollError::NoRewardToClaim);
+
+      self.claimed_rewards.insert((poll_id, voter), &reward);
+
+      if self.env().transfer(voter, reward).is_err() {
+        return Err(PollError::CannotTransferReward);
+      }
+
       Ok(())
     }
 
+    /// Get reward distribution of a poll
+    #[ink(message)]
+    pub fn poll_reward(&self, poll_id: PollId) -> Option<PollReward> {
+      self.poll_rewards.get(poll_id)
+    }
+
+    /// Get claimable reward of a voter for a poll
+    #[ink(message)]
+    pub fn pending_reward(&self, poll_id: PollId, who: AccountId) -> PollResult<Balance> {
+      let reward = self.poll_rewards.get(poll_id).ok_or(PollError::RewardsNotDistributed)?;
+
+      if self.claimed_rewards.contains((poll_id, who)) {
+        return Ok(0);
+      }
+
+      let voted_option = self.votes_voters.get((poll_id, who));
+      if reward.winning_option.is_some() && voted_option == reward.winning_option {
+        Ok(reward.share)
+      } else {
+        Ok(0)
+      }
+    }
+
     /// Get polls by ids
     #[ink(message)]
     pub fn polls_by_ids(&self, ids: Vec<PollId>) -> Vec<(PollId, Option<Poll>)> {
@@ -148,15 +331,11 @@ mod polls {
     #[ink(message)]
     pub fn poll_votes(&self, poll_id: PollId) -> PollResult<PollVotes> {
       let poll = self.polls.get(poll_id).ok_or(PollError::PollNotFound)?;
-      let mut total_votes: u32 = 0;
-      let mut votes_by_options: Vec<(OptionIndex, u32)> = Vec::new();
-
-      for index in 0..(poll.options.len()) {
-        let option_index = index as u32;
-        let votes_by_option = self.votes_counters.get((poll_id, option_index)).unwrap_or_default();
-        total_votes = total_votes.saturating_add(votes_by_option);
-        votes_by_options.push((option_index, votes_by_option));
-      }
+      let votes_by_options: Vec<(OptionIndex, u32)> = (0..(poll.options.len() as u32))
+        .map(|option_index| (option_index, self.votes_counters.get((poll_id, option_index)).unwrap_or_default()))
+        .collect();
+      let total_votes = self.cached_total_votes.get(poll_id)
+        .unwrap_or_else(|| votes_by_options.iter().map(|(_, votes)| votes).sum());
 
       let caller = self.env().caller();
       let voted_option = self.votes_voters.get((poll_id, caller));
@@ -176,36 +355,111 @@ mod polls {
       let _ = poll.options.get(option_index as usize).ok_or(PollError::InvalidOptionIndex)?;
 
       let voter = self.env().caller();
+      if !poll.mandatory && self.has_pending_mandatory_vote(voter) {
+        return Err(PollError::MandatoryVotePending);
+      }
+
       let maybe_voted_option = self.votes_voters.get((poll_id, voter));
       if let Some(voted_option) = maybe_voted_option {
         let votes_counter = self.votes_counters.get((poll_id, voted_option)).unwrap_or_default();
         self.votes_counters.insert((poll_id, voted_option), &votes_counter.saturating_sub(1));
+      } else {
+        let total_votes = self.total_votes(poll_id, &poll);
+        self.cached_total_votes.insert(poll_id, &total_votes.saturating_add(1));
       }
 
       let new_votes_counter = self.votes_counters.get((poll_id, option_index)).unwrap_or_default();
       self.votes_counters.insert((poll_id, option_index), &new_votes_counter.saturating_add(1));
       self.votes_voters.insert((poll_id, voter), &option_index);
 
+      if !self.has_votes(poll_id) {
+        self.has_votes.insert(poll_id, &true);
+      }
+
+      Ok(())
+    }
+
+    /// Recompute the cached total votes of a poll from votes of its options
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn invalidate_poll_cache(&mut self, poll_id: PollId) -> PollResult<()> {
+      let poll = self.polls.get(poll_id).ok_or(PollError::PollNotFound)?;
+
+      self.cached_total_votes.remove(poll_id);
+      let total_votes = self.total_votes(poll_id, &poll);
+      self.cached_total_votes.insert(poll_id, &total_votes);
 
       Ok(())
     }
 
+    /// Check if any vote has been cast on a poll
+    #[ink(message)]
+    pub fn has_votes(&self, poll_id: PollId) -> bool {
+      self.has_votes.get(poll_id).unwrap_or_default()
+    }
+
     /// UnVote
     #[ink(message)]
     #[modifiers(only_active_member)]
     pub fn unvote(&mut self, poll_id: PollId) -> PollResult<()> {
-      let _ = self.ensure_active_poll(poll_id)?;
+      let poll = self.ensure_active_poll(poll_id)?;
 
       let voter = self.env().caller();
       let voted_option = self.votes_voters.get((poll_id, voter)).ok_or(PollError::VoteNotFound)?;
       self.votes_voters.remove((poll_id, voter));
 
+      let total_votes = self.total_votes(poll_id, &poll);
+      self.cached_total_votes.insert(poll_id, &total_votes.saturating_sub(1));
+
       let votes_counter = self.votes_counters.get((poll_id, voted_option)).unwrap_or_default();
       self.votes_counters.insert((poll_id, voted_option), &votes_counter.saturating_sub(1));
 
       Ok(())
     }
 
+    fn total_votes(&self, poll_id: PollId, poll: &Poll) -> u32 {
+      self.cached_total_votes.get(poll_id).unwrap_or_else(|| {
+        (0..(poll.options.len() as u32))
+          .map(|option_index| self.votes_counters.get((poll_id, option_index)).unwrap_or_default())
+          .sum()
+      })
+    }
+
+    fn validate_title(title: &str) -> PollResult<()> {
+      let len = title.chars().count();
+      ensure!(!title.trim().is_empty(), PollError::Custom(String::from("Poll title must not be blank")));
+      ensure!(
+        (MIN_TITLE_LEN..=MAX_TITLE_LEN).contains(&len),
+        PollError::Custom(String::from("Poll title must be 3-100 characters"))
+      );
+
+      Ok(())
+    }
+
+    fn validate_desc(desc: &Option<String>) -> PollResult<()> {
+      if let Some(value) = desc {
+        ensure!(
+          value.chars().count() <= MAX_DESC_LEN,
+          PollError::Custom(String::from("Poll description is at max 500 characters"))
+        );
+      }
+
+      Ok(())
+    }
+
+    fn validate_options(options: &[String]) -> PollResult<()> {
+      ensure!(
+        (MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()),
+        PollError::Custom(String::from("Poll must have 2-10 options"))
+      );
+      ensure!(
+        options.iter().all(|option| (1..=MAX_OPTION_LEN).contains(&option.chars().count())),
+        PollError::Custom(String::from("Poll option must be 1-100 characters"))
+      );
+
+      Ok(())
+    }
+
     fn ensure_active_poll(&self, poll_id: PollId) -> PollResult<Poll> {
       let poll = self.polls.get(poll_id).ok_or(PollError::PollNotFound)?;
 
@@ -218,4 +472,408 @@ mod polls {
       Ok(poll)
     }
   }
+
+  #[cfg(all(test, feature = "e2e-tests"))]
+  mod e2e_tests {
+    use super::*;
+    use ink_e2e::build_message;
+    use motherspace::MotherSpaceRef;
+    use shared::traits::space_profile::SpaceInfo;
+    use space::SpaceRef;
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+    type E2EClient = ink_e2e::Client<ink_e2e::PolkadotConfig, DefaultEnvironment>;
+
+    /// Deploy motherspace & a free pay to join space owned by alice
+    async fn deploy_space(client: &mut E2EClient) -> AccountId {
+      let space_code = client
+        .upload("space", &ink_e2e::alice(), None)
+        .await
+        .expect("upload space failed")
+        .code_hash;
+
+      let motherspace_id = client
+        .instantiate("motherspace", &ink_e2e::alice(), MotherSpaceRef::new(space_code, ink_e2e::account_id(ink_e2e::AccountKeyring::Alice)), 0, None)
+        .await
+        .expect("instantiate motherspace failed")
+        .account_id;
+
+      let info = SpaceInfo { name: String::from("InSpace Test"), desc: None, logo: None };
+      let deploy_new_space = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.deploy_new_space(info, None, None, None));
+
+      let (space_id, _) = client
+        .call(&ink_e2e::alice(), deploy_new_space, 0, None)
+        .await
+        .expect("deploy space failed")
+        .return_value()
+        .expect("deploy space returned error");
+
+      space_id
+    }
+
+    async fn deploy_polls(client: &mut E2EClient, space_id: AccountId) -> AccountId {
+      let constructor = PollsRef::new(space_id, ink_e2e::account_id(ink_e2e::AccountKeyring::Alice), Vec::new());
+
+      client
+        .instantiate("polls", &ink_e2e::alice(), constructor, 0, None)
+        .await
+        .expect("instantiate polls failed")
+        .account_id
+    }
+
+    async fn join_space(client: &mut E2EClient, space_id: AccountId, signer: &ink_e2e::Keypair, client_nonce: u32) {
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, client_nonce));
+
+      client.call(signer, pay_to_join, 0, None).await.expect("join space failed");
+    }
+
+    async fn new_poll(client: &mut E2EClient, polls_id: AccountId, mandatory: bool) -> PollId {
+      let new_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.new_poll(String::from("Poll title"), None, vec![String::from("Yes"), String::from("No")], None, mandatory));
+
+      client
+        .call(&ink_e2e::alice(), new_poll, 0, None)
+        .await
+        .expect("new poll failed")
+        .return_value()
+        .expect("new poll returned error")
+    }
+
+    async fn vote_dry_run(client: &mut E2EClient, polls_id: AccountId, signer: &ink_e2e::Keypair, poll_id: PollId) -> PollResult<()> {
+      let vote = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.vote(poll_id, 0));
+
+      client.call_dry_run(signer, &vote, 0, None).await.return_value()
+    }
+
+    async fn vote(client: &mut E2EClient, polls_id: AccountId, signer: &ink_e2e::Keypair, poll_id: PollId, option_index: OptionIndex) {
+      let vote = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.vote(poll_id, option_index));
+
+      client
+        .call(signer, vote, 0, None)
+        .await
+        .expect("vote failed")
+        .return_value()
+        .expect("vote returned error");
+    }
+
+    async fn unvote(client: &mut E2EClient, polls_id: AccountId, signer: &ink_e2e::Keypair, poll_id: PollId) {
+      let unvote = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.unvote(poll_id));
+
+      client
+        .call(signer, unvote, 0, None)
+        .await
+        .expect("unvote failed")
+        .return_value()
+        .expect("unvote returned error");
+    }
+
+    /// Get votes of a poll & check the cached total matches votes of its options
+    async fn consistent_poll_votes(client: &mut E2EClient, polls_id: AccountId, poll_id: PollId) -> PollVotes {
+      let poll_votes = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.poll_votes(poll_id));
+
+      let votes = client
+        .call_dry_run(&ink_e2e::alice(), &poll_votes, 0, None)
+        .await
+        .return_value()
+        .expect("poll votes returned error");
+
+      let options_total: u32 = votes.votes_by_options.iter().map(|(_, votes)| votes).sum();
+      assert_eq!(votes.total_votes, options_total);
+
+      votes
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn cached_total_votes_stays_consistent(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      join_space(&mut client, space_id, &ink_e2e::charlie(), 1).await;
+
+      let new_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.new_poll(String::from("Poll title"), None, vec![String::from("Yes"), String::from("No")], None, false));
+      let poll_id = client
+        .call(&ink_e2e::alice(), new_poll, 0, None)
+        .await
+        .expect("new poll failed")
+        .return_value()
+        .expect("new poll returned error");
+
+      // vote
+      vote(&mut client, polls_id, &ink_e2e::bob(), poll_id, 0).await;
+      vote(&mut client, polls_id, &ink_e2e::charlie(), poll_id, 0).await;
+      let votes = consistent_poll_votes(&mut client, polls_id, poll_id).await;
+      assert_eq!(votes.total_votes, 2);
+      assert_eq!(votes.votes_by_options, vec![(0, 2), (1, 0)]);
+
+      // vote change
+      vote(&mut client, polls_id, &ink_e2e::bob(), poll_id, 1).await;
+      let votes = consistent_poll_votes(&mut client, polls_id, poll_id).await;
+      assert_eq!(votes.total_votes, 2);
+      assert_eq!(votes.votes_by_options, vec![(0, 1), (1, 1)]);
+
+      // unvote
+      unvote(&mut client, polls_id, &ink_e2e::charlie(), poll_id).await;
+      let votes = consistent_poll_votes(&mut client, polls_id, poll_id).await;
+      assert_eq!(votes.total_votes, 1);
+      assert_eq!(votes.votes_by_options, vec![(0, 0), (1, 1)]);
+
+      // recomputing the cache does not change anything
+      let invalidate_poll_cache = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.invalidate_poll_cache(poll_id));
+      client.call(&ink_e2e::alice(), invalidate_poll_cache, 0, None).await.expect("invalidate poll cache failed");
+      let votes = consistent_poll_votes(&mut client, polls_id, poll_id).await;
+      assert_eq!(votes.total_votes, 1);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn new_poll_returns_new_poll_id(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+
+      let titles = ["First poll", "Second poll", "Third poll"];
+      let mut poll_ids = Vec::new();
+      for title in titles {
+        let new_poll = build_message::<PollsRef>(polls_id.clone())
+          .call(|polls| polls.new_poll(String::from(title), None, vec![String::from("Yes"), String::from("No")], None, false));
+        let poll_id = client
+          .call(&ink_e2e::alice(), new_poll, 0, None)
+          .await
+          .expect("new poll failed")
+          .return_value()
+          .expect("new poll returned error");
+        poll_ids.push(poll_id);
+      }
+      assert_eq!(poll_ids, vec![0, 1, 2]);
+
+      let polls_by_ids = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.polls_by_ids(vec![0, 1, 2, 3]));
+      let polls = client.call_dry_run(&ink_e2e::alice(), &polls_by_ids, 0, None).await.return_value();
+      for (index, title) in titles.iter().enumerate() {
+        let (poll_id, maybe_poll) = &polls[index];
+        assert_eq!(*poll_id, index as PollId);
+        assert_eq!(maybe_poll.as_ref().expect("poll not found").title, *title);
+      }
+      assert!(polls[3].1.is_none());
+
+      Ok(())
+    }
+
+    fn options(values: &[&str]) -> Vec<String> {
+      values.iter().map(|&value| String::from(value)).collect()
+    }
+
+    async fn update_options_dry_run(client: &mut E2EClient, polls_id: AccountId, poll_id: PollId, values: &[&str]) -> PollResult<()> {
+      let update_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.update_poll(poll_id, None, None, Some(options(values)), None));
+
+      client.call_dry_run(&ink_e2e::alice(), &update_poll, 0, None).await.return_value()
+    }
+
+    async fn has_votes(client: &mut E2EClient, polls_id: AccountId, poll_id: PollId) -> bool {
+      let has_votes = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.has_votes(poll_id));
+
+      client.call_dry_run(&ink_e2e::alice(), &has_votes, 0, None).await.return_value()
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn options_can_only_be_appended_after_voting_started(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+
+      // options can be fully changed before any vote
+      let poll_id = new_poll(&mut client, polls_id, false).await;
+      assert!(!has_votes(&mut client, polls_id, poll_id).await);
+      assert_eq!(update_options_dry_run(&mut client, polls_id, poll_id, &["No", "Yes"]).await, Ok(()));
+      assert_eq!(update_options_dry_run(&mut client, polls_id, poll_id, &["Maybe", "Never"]).await, Ok(()));
+
+      vote(&mut client, polls_id, &ink_e2e::bob(), poll_id, 0).await;
+      assert!(has_votes(&mut client, polls_id, poll_id).await);
+
+      let cannot_reorder = Err(PollError::Custom(String::from("Cannot reorder options after voting started")));
+      assert_eq!(update_options_dry_run(&mut client, polls_id, poll_id, &["No", "Yes"]).await, cannot_reorder);
+      assert_eq!(update_options_dry_run(&mut client, polls_id, poll_id, &["Yes", "Maybe", "No"]).await, cannot_reorder);
+      assert_eq!(update_options_dry_run(&mut client, polls_id, poll_id, &["Yes", "No", "Maybe"]).await, Ok(()));
+
+      // the flag stays once votes are withdrawn
+      unvote(&mut client, polls_id, &ink_e2e::bob(), poll_id).await;
+      assert!(has_votes(&mut client, polls_id, poll_id).await);
+      assert_eq!(update_options_dry_run(&mut client, polls_id, poll_id, &["No", "Yes"]).await, cannot_reorder);
+
+      Ok(())
+    }
+
+    const REWARD_POOL: Balance = 1_000_000;
+
+    async fn new_rewarded_poll(client: &mut E2EClient, polls_id: AccountId) -> PollId {
+      let new_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.new_poll(String::from("Poll title"), None, vec![String::from("Yes"), String::from("No")], None, false));
+
+      client
+        .call(&ink_e2e::alice(), new_poll, REWARD_POOL, None)
+        .await
+        .expect("new poll failed")
+        .return_value()
+        .expect("new poll returned error")
+    }
+
+    /// Close the poll & distribute its rewards in a later block, once the poll is expired
+    async fn distribute_poll_rewards(client: &mut E2EClient, polls_id: AccountId, poll_id: PollId) {
+      let close_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.close_poll(poll_id));
+      client.call(&ink_e2e::alice(), close_poll, 0, None).await.expect("close poll failed");
+
+      let distribute_poll_rewards = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.distribute_poll_rewards(poll_id));
+      client
+        .call(&ink_e2e::dave(), distribute_poll_rewards, 0, None)
+        .await
+        .expect("distribute poll rewards failed")
+        .return_value()
+        .expect("distribute poll rewards returned error");
+    }
+
+    async fn claim_reward_dry_run(client: &mut E2EClient, polls_id: AccountId, signer: &ink_e2e::Keypair, poll_id: PollId) -> PollResult<()> {
+      let claim_poll_reward = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.claim_poll_reward(poll_id));
+
+      client.call_dry_run(signer, &claim_poll_reward, 0, None).await.return_value()
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn poll_reward_is_shared_by_winning_voters(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+      for signer in [ink_e2e::bob(), ink_e2e::charlie(), ink_e2e::dave()] {
+        join_space(&mut client, space_id, &signer, 1).await;
+      }
+
+      let poll_id = new_rewarded_poll(&mut client, polls_id).await;
+      vote(&mut client, polls_id, &ink_e2e::bob(), poll_id, 0).await;
+      vote(&mut client, polls_id, &ink_e2e::charlie(), poll_id, 0).await;
+      vote(&mut client, polls_id, &ink_e2e::dave(), poll_id, 1).await;
+
+      // rewards can only be distributed once the poll is finalized
+      let distribute_poll_rewards_now = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.distribute_poll_rewards(poll_id));
+      let result = client.call_dry_run(&ink_e2e::dave(), &distribute_poll_rewards_now, 0, None).await.return_value();
+      assert_eq!(result, Err(PollError::PollNotFinalized));
+
+      distribute_poll_rewards(&mut client, polls_id, poll_id).await;
+      let poll_reward = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.poll_reward(poll_id));
+      let reward = client.call_dry_run(&ink_e2e::dave(), &poll_reward, 0, None).await.return_value().expect("rewards not distributed");
+      assert_eq!(reward.winning_option, Some(0));
+      assert_eq!(reward.share, REWARD_POOL / 2);
+
+      let result = client.call_dry_run(&ink_e2e::dave(), &distribute_poll_rewards_now, 0, None).await.return_value();
+      assert_eq!(result, Err(PollError::RewardsAlreadyDistributed));
+
+      // winning voters claim their share once, other voters have nothing to claim
+      let polls_balance = client.balance(polls_id.clone()).await.expect("polls balance failed");
+      let claim_poll_reward = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.claim_poll_reward(poll_id));
+      client.call(&ink_e2e::bob(), claim_poll_reward, 0, None).await.expect("claim poll reward failed");
+      assert_eq!(client.balance(polls_id.clone()).await.expect("polls balance failed"), polls_balance - REWARD_POOL / 2);
+
+      assert_eq!(claim_reward_dry_run(&mut client, polls_id, &ink_e2e::bob(), poll_id).await, Err(PollError::NoRewardToClaim));
+      assert_eq!(claim_reward_dry_run(&mut client, polls_id, &ink_e2e::charlie(), poll_id).await, Ok(()));
+      assert_eq!(claim_reward_dry_run(&mut client, polls_id, &ink_e2e::dave(), poll_id).await, Err(PollError::NoRewardToClaim));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn tied_poll_reward_is_refunded(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      join_space(&mut client, space_id, &ink_e2e::charlie(), 1).await;
+
+      let poll_id = new_rewarded_poll(&mut client, polls_id).await;
+      vote(&mut client, polls_id, &ink_e2e::bob(), poll_id, 0).await;
+      vote(&mut client, polls_id, &ink_e2e::charlie(), poll_id, 1).await;
+
+      let polls_balance = client.balance(polls_id.clone()).await.expect("polls balance failed");
+      distribute_poll_rewards(&mut client, polls_id, poll_id).await;
+
+      // no winner, the whole pool goes back to the poll author
+      let poll_reward = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.poll_reward(poll_id));
+      let reward = client.call_dry_run(&ink_e2e::dave(), &poll_reward, 0, None).await.return_value().expect("rewards not distributed");
+      assert_eq!(reward.winning_option, None);
+      assert_eq!(reward.share, 0);
+      assert_eq!(client.balance(polls_id.clone()).await.expect("polls balance failed"), polls_balance - REWARD_POOL);
+
+      for signer in [ink_e2e::bob(), ink_e2e::charlie()] {
+        assert_eq!(claim_reward_dry_run(&mut client, polls_id, &signer, poll_id).await, Err(PollError::NoRewardToClaim));
+      }
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn mandatory_poll_blocks_other_votes_until_voted(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+
+      let poll_id = new_poll(&mut client, polls_id, false).await;
+      let mandatory_poll_id = new_poll(&mut client, polls_id, true).await;
+
+      // blocked
+      assert_eq!(vote_dry_run(&mut client, polls_id, &ink_e2e::bob(), poll_id).await, Err(PollError::MandatoryVotePending));
+
+      // unblocked once the mandatory poll is voted
+      vote(&mut client, polls_id, &ink_e2e::bob(), mandatory_poll_id, 0).await;
+      vote(&mut client, polls_id, &ink_e2e::bob(), poll_id, 0).await;
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn reopened_mandatory_poll_blocks_votes_again(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let polls_id = deploy_polls(&mut client, space_id).await;
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+
+      let poll_id = new_poll(&mut client, polls_id, false).await;
+      let mandatory_poll_id = new_poll(&mut client, polls_id, true).await;
+
+      // closed mandatory polls do not block
+      let close_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.close_poll(mandatory_poll_id));
+      client.call(&ink_e2e::alice(), close_poll, 0, None).await.expect("close poll failed");
+      assert_eq!(vote_dry_run(&mut client, polls_id, &ink_e2e::bob(), poll_id).await, Ok(()));
+
+      // reopening the poll via its expiry blocks again
+      let update_poll = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.update_poll(mandatory_poll_id, None, None, None, None));
+      client
+        .call(&ink_e2e::alice(), update_poll, 0, None)
+        .await
+        .expect("update poll failed")
+        .return_value()
+        .expect("update poll returned error");
+
+      let list_pending_mandatory_polls = build_message::<PollsRef>(polls_id.clone())
+        .call(|polls| polls.list_pending_mandatory_polls(ink_e2e::account_id(ink_e2e::AccountKeyring::Bob)));
+      let pending_polls = client.call_dry_run(&ink_e2e::alice(), &list_pending_mandatory_polls, 0, None).await.return_value();
+      assert_eq!(pending_polls, vec![mandatory_poll_id]);
+      assert_eq!(vote_dry_run(&mut client, polls_id, &ink_e2e::bob(), poll_id).await, Err(PollError::MandatoryVotePending));
+
+      Ok(())
+    }
+  }
 }
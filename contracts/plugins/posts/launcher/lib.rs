@@ -20,11 +20,12 @@ mod posts_launcher {
   }
 
   impl plugin_launcher::Instantiator for PostsLauncher {
-    fn _initiate_new_plugin(&self, space_id: AccountId, launcher_id: AccountId, salt: &[u8]) -> Result<AccountId, LauncherError> {
+    fn _initiate_new_plugin(&self, space_id: AccountId, launcher_id: AccountId, salt: &[u8], init_data: &[u8]) -> Result<AccountId, LauncherError> {
       let input =
         ExecutionInput::new(Selector::new(ink::selector_bytes!("new")))
           .push_arg(space_id)
-          .push_arg(launcher_id);
+          .push_arg(launcher_id)
+          .push_arg(init_data);
 
       let new_contract: PostsRef = build_create::<PostsRef>()
         .code_hash(self.latest_plugin_code())
@@ -4,13 +4,21 @@ pub use posts::{PostsRef};
 
 #[openbrush::contract]
 mod posts {
-  use ink::prelude::{vec::Vec, vec};
+  use ink::env::call::{build_call, ExecutionInput, Selector};
+  use ink::env::DefaultEnvironment;
+  use ink::prelude::vec::Vec;
+  use ink::primitives::Key;
   use ink::storage::{Mapping, Lazy};
+  use ink::storage::traits::StorageKey;
   use openbrush::{modifiers, traits::{Storage, String}};
+  use scale::Decode;
+  use shared::ensure;
+  use shared::helpers::validation::validate_ipfs_cid;
   use shared::traits::codehash::*;
   use shared::traits::plugin_base::*;
 
   type PostResult<T> = core::result::Result<T, PostError>;
+  type PluginId = [u8; 4];
 
   #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Clone)]
   #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -18,6 +26,27 @@ mod posts {
     Custom(String),
     PluginError(PluginError),
     PostNotExisted,
+    MandatoryVotePending,
+    FeatureDisabled,
+    ReactionNotFound,
+    PendingPostLimitExceeded(u32),
+    ContentTooLarge,
+    InvalidCid,
+    TooManyPinnedPosts,
+    PostLocked,
+    InvalidTags,
+    PollNotFound,
+    CommentsDisabled,
+    AlreadyReported,
+    QueueFull,
+    CooldownActive(Timestamp),
+    CannotTipOwnPost,
+    NoTipsToClaim,
+    CannotTransferTips,
+    PostArchived,
+    PostingFrozen,
+    PollsUnavailable,
+    TooManyTags,
   }
 
   impl From<PluginError> for PostError {
@@ -26,6 +55,22 @@ mod posts {
     }
   }
 
+  const MARKDOWN_ENABLED_FLAG: &str = "markdown_enabled";
+  const DEFAULT_PENDING_POST_LIMIT: u32 = 10;
+  const DEFAULT_PENDING_QUEUE_CAP: u32 = 500;
+  const PENDING_QUEUE_CAP_CEILING: u32 = 1_000;
+  const DEFAULT_MAX_CONTENT_LEN: u32 = 5_000;
+  const MAX_CONTENT_LEN_CEILING: u32 = 20_000;
+  const MAX_PINNED_POSTS: usize = 10;
+  const MAX_POST_REVISIONS: u32 = 10;
+  const MAX_TAGS_PER_POST: usize = 5;
+  const MAX_TAG_LEN: usize = 24;
+  const MAX_DISTINCT_TAGS: usize = 500;
+  const MAX_REPORT_REASON_LEN: usize = 200;
+  const MAX_TITLE_LEN: usize = 200;
+  const DEFAULT_PENDING_POST_EXPIRY_SECS: u64 = 30 * 24 * 60 * 60; // 30 days
+  const MAX_CLOSED_PENDING_POSTS_PER_AUTHOR: usize = 20;
+
   type PostId = u32;
   type Nonce = u32;
 
@@ -49,6 +94,15 @@ mod posts {
     ActiveMemberWithApproval,
   }
 
+  /// Who can read posts?
+  #[derive(Clone, Default, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub enum ReadPerm {
+    #[default]
+    Public,
+    ActiveMember,
+  }
+
   #[derive(Clone, Debug, scale::Decode, scale::Encode)]
   #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
   pub enum PostCreationStatus {
@@ -61,6 +115,9 @@ mod posts {
   pub enum PostContent {
     Raw(String),
     IpfsCid(String),
+    MarkdownRaw(String),
+    /// Rich post with a title, body is markdown
+    Structured { title: String, body: String },
   }
 
   #[derive(Clone, Debug, scale::Decode, scale::Encode)]
@@ -88,6 +145,87 @@ mod posts {
     created_at: Timestamp,
     updated_at: Option<Timestamp>,
     parent_id: Option<PostId>,
+    /// Set when a pending post is approved and published
+    approved_at: Option<Timestamp>,
+    /// (posts plugin address, post id) of the original post if this is a shared post
+    shared_from: Option<(AccountId, PostId)>,
+    status: PostStatus,
+    /// Author or space owner who last edited the post
+    updated_by: Option<AccountId>,
+    /// Locked posts can not be edited or deleted
+    locked: bool,
+    /// Scheduled posts are hidden until this time
+    publish_at: Option<Timestamp>,
+    /// Poll id in the polls plugin of the space
+    linked_poll: Option<u32>,
+    comments_enabled: bool,
+  }
+
+  /// Post layout before status & other fields were appended, only used to read posts stored before the upgrade
+  #[derive(scale::Encode, scale::Decode)]
+  struct LegacyPost {
+    content: PostContent,
+    author: AccountId,
+    created_at: Timestamp,
+    updated_at: Option<Timestamp>,
+    parent_id: Option<PostId>,
+  }
+
+  impl From<LegacyPost> for Post {
+    fn from(legacy: LegacyPost) -> Self {
+      Post {
+        content: legacy.content,
+        author: legacy.author,
+        created_at: legacy.created_at,
+        updated_at: legacy.updated_at,
+        parent_id: legacy.parent_id,
+        approved_at: None,
+        shared_from: None,
+        status: PostStatus::Published,
+        updated_by: None,
+        locked: false,
+        publish_at: None,
+        linked_poll: None,
+        // comments were always allowed before they could be disabled
+        comments_enabled: true,
+      }
+    }
+  }
+
+  #[derive(Clone, Copy, Default, Debug, PartialEq, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub enum PostStatus {
+    #[default]
+    Published,
+    Deleted,
+    Archived,
+    /// Pending post which was not reviewed in time
+    Expired,
+    /// Pending post rejected by space owner
+    Rejected,
+  }
+
+  #[derive(Clone, Copy, Default, Debug, PartialEq, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub enum ReactionKind {
+    #[default]
+    Like,
+    Love,
+    Laugh,
+    Celebrate,
+    Wow,
+    Sad,
+  }
+
+  impl ReactionKind {
+    pub const ALL: [ReactionKind; 6] = [
+      ReactionKind::Like,
+      ReactionKind::Love,
+      ReactionKind::Laugh,
+      ReactionKind::Celebrate,
+      ReactionKind::Wow,
+      ReactionKind::Sad,
+    ];
   }
 
   #[derive(Clone, Debug, scale::Decode, scale::Encode)]
@@ -95,10 +233,76 @@ mod posts {
   pub struct PostRecord {
     post_id: PostId,
     post: Post,
+    /// Always 0 for pending posts
+    tips_total: Balance,
   }
 
   type PostsPage = Pagination<PostRecord>;
 
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct PostRecordWithStats {
+    post_id: PostId,
+    post: Post,
+    view_count: u32,
+    tips_total: Balance,
+  }
+
+  type PostsWithStatsPage = Pagination<PostRecordWithStats>;
+
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct PostRecordWithAuthor {
+    post_id: PostId,
+    post: Post,
+    author_name: Option<String>,
+  }
+
+  type PostsWithAuthorsPage = Pagination<PostRecordWithAuthor>;
+
+  /// Content of a post before an edit
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub struct PostRevision {
+    content: PostContent,
+    edited_by: AccountId,
+    edited_at: Timestamp,
+  }
+
+  type PostRevisionsPage = Pagination<PostRevision>;
+
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct ReportedPostRecord {
+    post_id: PostId,
+    post: Post,
+    report_count: u32,
+  }
+
+  type ReportedPostsPage = Pagination<ReportedPostRecord>;
+
+  #[ink(event)]
+  pub struct PendingPostUpdated {
+    #[ink(topic)]
+    pending_post_id: PostId,
+    updated_at: Timestamp,
+  }
+
+  #[ink(event)]
+  pub struct PostingFrozen {}
+
+  #[ink(event)]
+  pub struct PostingUnfrozen {}
+
+  #[ink(event)]
+  pub struct PostTipped {
+    #[ink(topic)]
+    post_id: PostId,
+    #[ink(topic)]
+    from: AccountId,
+    amount: Balance,
+  }
+
   #[ink(storage)]
   #[derive(Default, Storage)]
   pub struct Posts {
@@ -107,19 +311,88 @@ mod posts {
 
     posts: Mapping<PostId, Post>,
     posts_nonce: Lazy<Nonce>,
+    // number of posts which are not deleted
+    active_posts_count: Lazy<u32>,
 
     post_perm: Lazy<PostPerm>,
+    read_perm: Lazy<ReadPerm>,
+    max_content_len: Lazy<u32>,
+    comments_default_enabled: Lazy<bool>,
 
     comments: Mapping<PostId, Post>,
     post_to_comments: Mapping<PostId, Vec<PostId>>,
+    comment_counts: Mapping<PostId, u32>,
+    // one level of replies, reply id -> top-level comment id
+    comment_reply_to: Mapping<PostId, PostId>,
+    comment_replies: Mapping<PostId, Vec<PostId>>,
     comments_nonce: Lazy<Nonce>,
 
     pending_posts: Mapping<PostId, Post>,
     author_to_pending_posts: Mapping<AccountId, Vec<PostId>>,
     pending_post_ids: Lazy<Vec<PostId>>,
     pending_posts_nonce: Lazy<Nonce>,
+    pending_post_limit: Lazy<u32>,
+    pending_queue_cap: Lazy<u32>,
+    pending_post_tags: Mapping<PostId, Vec<String>>,
+    pending_post_expiry_secs: Lazy<Option<u64>>,
+    // latest expired and rejected pending posts of each author, kept so authors know what happened
+    author_to_expired_pending_posts: Mapping<AccountId, Vec<PostId>>,
+    author_to_rejected_pending_posts: Mapping<AccountId, Vec<PostId>>,
+    // new pending post id -> rejected or expired pending post it was resubmitted from
+    resubmission_of: Mapping<PostId, PostId>,
+
+    // minimum interval between posts of an author, space owner is exempt
+    post_cooldown_secs: Lazy<u64>,
+    // temporarily stops new posts, comments and edits without changing post permission
+    posting_frozen: Lazy<bool>,
+    last_post_at: Mapping<AccountId, Timestamp>,
 
     pinned_posts: Lazy<Vec<PostId>>,
+    // posts waiting for their publish time, published ones are pruned on new scheduled posts
+    scheduled_post_ids: Lazy<Vec<PostId>>,
+
+    // plugin id of the space's polls plugin, used to check linked polls & mandatory poll participation
+    polls_plugin_id: Lazy<Option<PluginId>>,
+
+    shared_posts_count: Lazy<u32>,
+    // pending shared post id -> author of the source post, credited on approval
+    pending_shared_post_authors: Mapping<PostId, AccountId>,
+
+    bookmarks: Mapping<(AccountId, PostId), bool>,
+    member_bookmarks: Mapping<AccountId, Vec<PostId>>,
+    post_bookmark_count: Mapping<PostId, u32>,
+
+    reactions: Mapping<(PostId, AccountId), ReactionKind>,
+    reaction_counts: Mapping<PostId, u32>,
+    reaction_kind_counts: Mapping<(PostId, ReactionKind), u32>,
+
+    tips_total: Mapping<PostId, Balance>,
+    tippers_count: Mapping<PostId, u32>,
+    post_tippers: Mapping<(PostId, AccountId), bool>,
+    // tips that could not be transferred to authors right away, claimable later
+    unclaimed_tips: Mapping<AccountId, Balance>,
+
+    // reason of each report, one report per member per post
+    reports: Mapping<(PostId, AccountId), String>,
+    post_reporters: Mapping<PostId, Vec<AccountId>>,
+    reported_post_ids: Lazy<Vec<PostId>>,
+
+    author_to_posts: Mapping<AccountId, Vec<PostId>>,
+    author_post_counts: Mapping<AccountId, u32>,
+
+    // tags are stored in lowercase
+    post_tags: Mapping<PostId, Vec<String>>,
+    tag_to_posts: Mapping<Vec<u8>, Vec<PostId>>,
+    tag_post_counts: Mapping<Vec<u8>, u32>,
+    // tags used by at least one post, tags are pruned once their last post drops them
+    all_tags: Lazy<Vec<String>>,
+
+    // only the last 10 revisions of each post are kept
+    post_revisions: Mapping<(PostId, u32), PostRevision>,
+    post_revisions_count: Mapping<PostId, u32>,
+
+    view_counts: Mapping<PostId, u32>,
+    viewed_by: Mapping<(PostId, AccountId), bool>,
   }
 
   impl CodeHash for Posts {}
@@ -127,38 +400,57 @@ mod posts {
 
   impl Posts {
     #[ink(constructor)]
-    pub fn new(space_id: AccountId, launcher_id: AccountId) -> Self {
+    /// `init_data` is an optional scale encoded `PostPerm` to start with
+    pub fn new(space_id: AccountId, launcher_id: AccountId, init_data: Vec<u8>) -> Self {
       let mut one = Self::default();
       plugin_base::PluginBase::_init(&mut one, space_id, launcher_id);
 
+      if let Ok(post_perm) = PostPerm::decode(&mut &init_data[..]) {
+        one.post_perm.set(&post_perm);
+      }
+
       one
     }
 
+    /// Create a new post, posts with a future `publish_at` are kept hidden until then
     #[ink(message)]
-    pub fn new_post(&mut self, content: PostContent) -> PostResult<(PostCreationStatus, u32)> {
-      self._ensure_post_permission()?;
-
-      // TODO verify post content
-
+    pub fn new_post(&mut self, content: PostContent, publish_at: Option<Timestamp>, tags: Vec<String>, linked_poll: Option<u32>) -> PostResult<(PostCreationStatus, u32)> {
       let caller = Self::env().caller();
-      let permission = self.post_perm();
+      let is_space_owner = self._ensure_can_create_post(caller)?;
+      self._ensure_valid_content(&content)?;
+      let tags = Self::_normalize_tags(tags)?;
+      self._ensure_valid_linked_poll(linked_poll)?;
+      if let Some(publish_at) = publish_at {
+        ensure!(publish_at > self.env().block_timestamp(), PostError::Custom(String::from("Publish time must be in the future")));
+      }
 
-      Ok(match permission {
-        PostPerm::SpaceOwner | PostPerm::ActiveMember => (PostCreationStatus::Created, self._new_post(content)?),
-        PostPerm::ActiveMemberWithApproval => {
-          let space_owner = self._space_owner_id();
+      let (status, id) = if self._requires_approval(is_space_owner) {
+        (PostCreationStatus::Pending, self._new_pending_post(content, publish_at, linked_poll)?)
+      } else {
+        (PostCreationStatus::Created, self._new_post(content, publish_at, linked_poll)?)
+      };
 
-          if caller == space_owner {
-            (PostCreationStatus::Created, self._new_post(content)?)
-          } else {
-            (PostCreationStatus::Pending, self._new_pending_post(content)?)
-          }
+      match status {
+        PostCreationStatus::Created => self._update_post_tags(id, tags)?,
+        // tags are indexed once the post is approved
+        PostCreationStatus::Pending => {
+          self.pending_post_tags.insert(id, &tags);
         }
-      })
+      }
+
+      // pending posts count toward the cooldown too
+      if !is_space_owner {
+        self.last_post_at.insert(caller, &self.env().block_timestamp());
+      }
+
+      Ok((status, id))
     }
 
+    /// List pending posts for review, only space owner can see the full queue
     #[ink(message)]
-    pub fn list_pending_posts(&self, from: u32, per_page: u32) -> PostsPage {
+    pub fn list_pending_posts(&self, from: u32, per_page: u32) -> PostResult<PostsPage> {
+      self._ensure_space_owner()?;
+
       let per_page = per_page.min(50); // limit per page at max 50 items
       let posts = self.pending_post_ids.get_or_default();
       let last_position = from.saturating_add(per_page);
@@ -167,34 +459,49 @@ mod posts {
       let page: Option<&[PostId]> = posts.get((from as usize)..(last_position.min(total) as usize));
       let items = match page {
         Some(list) => list.iter()
-          .map(|id| PostRecord {
-            post_id: *id,
-            post: self.pending_posts.get(id).unwrap(),
-          })
+          .filter_map(|&id| self._stored_pending_post(id).map(|post| Self::_pending_post_record(id, post)))
           .collect(),
         None => Vec::new()
       };
 
-      PostsPage {
+      Ok(PostsPage {
         items,
         from,
         per_page,
         has_next_page: last_position < total,
         total,
+      })
+    }
+
+    /// Get pending posts by ids, at max 50 ids.
+    /// Space owner can see all pending posts, members only see their own
+    #[ink(message)]
+    pub fn pending_posts_by_ids(&self, ids: Vec<PostId>) -> PostResult<Vec<(PostId, Option<Post>)>> {
+      let caller = self.env().caller();
+      let is_space_owner = caller == self._space_owner_id();
+      if !is_space_owner {
+        self._ensure_active_member()?;
       }
+
+      Ok(
+        ids.iter()
+          .take(50)
+          .map(|&id| (id, self._get_pending_post(id).filter(|post| is_space_owner || post.author == caller)))
+          .collect()
+      )
     }
 
     #[ink(message)]
-    pub fn pending_posts_by_author(&self, who: Option<AccountId>) -> Vec<PostRecord> {
+    pub fn pending_posts_by_author(&self, who: Option<AccountId>) -> PostResult<Vec<PostRecord>> {
+      self._ensure_read_permission()?;
       let author = who.unwrap_or(self.env().caller());
       let pending_post_ids = self.author_to_pending_posts.get(author).unwrap_or_default();
 
-      pending_post_ids.iter()
-        .map(|&id| PostRecord {
-          post_id: id,
-          post: self.pending_posts.get(id).unwrap(),
-        })
-        .collect()
+      Ok(
+        pending_post_ids.iter()
+          .filter_map(|&id| self._stored_pending_post(id).map(|post| Self::_pending_post_record(id, post)))
+          .collect()
+      )
     }
 
     #[ink(message)]
@@ -205,21 +512,37 @@ mod posts {
       let mut not_found_count: u32 = 0;
 
       let mut submitted_posts_id: Vec<u32> = Vec::new();
+      let mut rejected_posts_id: Vec<u32> = Vec::new();
       for approval in approvals {
         let (pending_post_id, approved) = approval;
 
-        if let Some(pending_post) = self.pending_posts.get(pending_post_id) {
+        if let Some(mut pending_post) = self._get_pending_post(pending_post_id) {
           submitted_posts_id.push(pending_post_id);
 
           if approved {
             let new_post_id = self.posts_nonce.get_or_default();
             let next_post_nonce = new_post_id.checked_add(1).expect("Exceeds number of posts!");
 
+            pending_post.approved_at = Some(Self::env().block_timestamp());
+            if let Some(source_author) = self.pending_shared_post_authors.take(pending_post_id) {
+              pending_post.author = source_author;
+              self.shared_posts_count.set(&self.shared_posts_count().saturating_add(1));
+            }
             self.posts.insert(new_post_id, &pending_post);
+            self._add_author_post(pending_post.author, new_post_id);
+            self._update_post_tags(new_post_id, self.pending_post_tags.get(pending_post_id).unwrap_or_default())?;
             self.posts_nonce.set(&next_post_nonce);
+            self._increase_active_posts_count();
+
+            if !self._is_published(&pending_post) {
+              let mut scheduled_post_ids = self.scheduled_post_ids.get_or_default();
+              scheduled_post_ids.push(new_post_id);
+              self.scheduled_post_ids.set(&scheduled_post_ids);
+            }
 
             approved_count = approved_count.saturating_add(1);
           } else {
+            rejected_posts_id.push(pending_post_id);
             rejected_count = rejected_count.saturating_add(1);
           }
         } else {
@@ -232,12 +555,17 @@ mod posts {
       self.pending_post_ids.set(&pending_posts);
 
       for post_id in submitted_posts_id {
-        let submitted_post = self.pending_posts.get(post_id).unwrap();
+        let submitted_post = self._stored_pending_post(post_id).unwrap();
         let mut author_to_id = self.author_to_pending_posts.get(submitted_post.author).unwrap();
         author_to_id.retain(|id| id != &post_id);
         self.author_to_pending_posts.insert(submitted_post.author, &author_to_id);
 
-        self.pending_posts.remove(post_id);
+        if rejected_posts_id.contains(&post_id) {
+          self._keep_closed_pending_post(post_id, submitted_post, PostStatus::Rejected);
+        } else {
+          self.pending_posts.remove(post_id);
+          self.pending_post_tags.remove(post_id);
+        }
       }
 
       Ok(ApprovalSubmissionResult {
@@ -247,9 +575,206 @@ mod posts {
       })
     }
 
+    /// Maximum number of pending posts per author
+    #[ink(message)]
+    pub fn pending_post_limit(&self) -> u32 {
+      self.pending_post_limit.get().unwrap_or(DEFAULT_PENDING_POST_LIMIT)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_pending_post_limit(&mut self, limit: u32) -> PostResult<()> {
+      self.pending_post_limit.set(&limit);
+
+      Ok(())
+    }
+
+    /// Maximum number of pending posts of all authors
+    #[ink(message)]
+    pub fn pending_queue_cap(&self) -> u32 {
+      self.pending_queue_cap.get().unwrap_or(DEFAULT_PENDING_QUEUE_CAP)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_pending_queue_cap(&mut self, cap: u32) -> PostResult<()> {
+      ensure!(cap <= PENDING_QUEUE_CAP_CEILING, PostError::Custom(String::from("Pending queue cap is at max 1000")));
+
+      self.pending_queue_cap.set(&cap);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn post_cooldown(&self) -> u64 {
+      self.post_cooldown_secs.get_or_default()
+    }
+
+    /// Minimum interval in seconds between posts of an author, 0 to disable
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_post_cooldown(&mut self, seconds: u64) -> PostResult<()> {
+      self.post_cooldown_secs.set(&seconds);
+
+      Ok(())
+    }
+
+    /// When an author can post again, None if there is no active cooldown
+    #[ink(message)]
+    pub fn next_post_available_at(&self, who: AccountId) -> Option<Timestamp> {
+      let cooldown = self.post_cooldown();
+      if cooldown == 0 {
+        return None;
+      }
+
+      let last_post_at = self.last_post_at.get(who)?;
+
+      Some(last_post_at.saturating_add(cooldown))
+    }
+
+    /// How long a pending post can wait for review
+    #[ink(message)]
+    pub fn pending_post_expiry(&self) -> u64 {
+      self.pending_post_expiry_secs.get_or_default().unwrap_or(DEFAULT_PENDING_POST_EXPIRY_SECS)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_pending_post_expiry(&mut self, seconds: u64) -> PostResult<()> {
+      ensure!(seconds > 0, PostError::Custom(String::from("Expiry must be greater than 0")));
+
+      self.pending_post_expiry_secs.set(&Some(seconds));
+
+      Ok(())
+    }
+
+    /// Expire pending posts waiting longer than the expiry, at max `limit` queued posts are checked per call.
+    /// Anyone can call this, returns number of swept posts
+    #[ink(message)]
+    pub fn sweep_expired_pending_posts(&mut self, limit: u32) -> u32 {
+      let now = self.env().block_timestamp();
+      let expiry = self.pending_post_expiry();
+      let mut pending_posts = self.pending_post_ids.get_or_default();
+
+      // queue order is not relied on, every checked post is compared against the expiry
+      let mut swept_ids: Vec<PostId> = Vec::new();
+      for &pending_post_id in pending_posts.iter().take(limit as usize) {
+        let Some(post) = self._stored_pending_post(pending_post_id) else {
+          swept_ids.push(pending_post_id);
+          continue;
+        };
+
+        if post.created_at.saturating_add(expiry) > now {
+          continue;
+        }
+
+        let mut author_pending_posts = self.author_to_pending_posts.get(post.author).unwrap_or_default();
+        author_pending_posts.retain(|&id| id != pending_post_id);
+        self.author_to_pending_posts.insert(post.author, &author_pending_posts);
+
+        self._keep_closed_pending_post(pending_post_id, post, PostStatus::Expired);
+
+        swept_ids.push(pending_post_id);
+      }
+
+      pending_posts.retain(|id| !swept_ids.contains(id));
+      self.pending_post_ids.set(&pending_posts);
+
+      swept_ids.len() as u32
+    }
+
+    #[ink(message)]
+    pub fn expired_pending_posts_by_author(&self, who: Option<AccountId>) -> PostResult<Vec<PostRecord>> {
+      self._ensure_read_permission()?;
+      let author = who.unwrap_or(self.env().caller());
+
+      Ok(
+        self.author_to_expired_pending_posts.get(author).unwrap_or_default()
+          .iter()
+          .filter_map(|&id| self._stored_pending_post(id).map(|post| Self::_pending_post_record(id, post)))
+          .collect()
+      )
+    }
+
+    #[ink(message)]
+    pub fn rejected_pending_posts_by_author(&self, who: Option<AccountId>) -> PostResult<Vec<PostRecord>> {
+      self._ensure_read_permission()?;
+      let author = who.unwrap_or(self.env().caller());
+
+      Ok(
+        self.author_to_rejected_pending_posts.get(author).unwrap_or_default()
+          .iter()
+          .filter_map(|&id| self._stored_pending_post(id).map(|post| Self::_pending_post_record(id, post)))
+          .collect()
+      )
+    }
+
+    /// Put a rejected or expired pending post back to the review queue, optionally with new content.
+    /// The old entry is consumed and linked to the new pending post
     #[ink(message)]
-    pub fn pending_posts_count(&self) -> u32 {
-      self.pending_post_ids.get_or_default().len() as u32
+    #[modifiers(only_active_member)]
+    pub fn resubmit_pending_post(&mut self, rejected_id: PostId, content: Option<PostContent>) -> PostResult<PostId> {
+      self._ensure_posting_not_frozen()?;
+      let post = self._stored_pending_post(rejected_id)
+        .filter(|post| post.status == PostStatus::Rejected || post.status == PostStatus::Expired)
+        .ok_or(PostError::PostNotExisted)?;
+
+      let caller = Self::env().caller();
+      if caller != post.author {
+        return Err(PluginError::UnAuthorized.into());
+      }
+
+      // resubmitting counts as posting again
+      let is_space_owner = caller == self._space_owner_id();
+      if !is_space_owner {
+        self._ensure_post_cooldown_passed(caller)?;
+      }
+
+      let content = content.unwrap_or_else(|| post.content.clone());
+      self._ensure_valid_content(&content)?;
+
+      // publish time in the past is dropped, the post is published on approval
+      let publish_at = post.publish_at.filter(|&publish_at| publish_at > self.env().block_timestamp());
+      let mut new_pending_post = self._build_post(caller, content, publish_at, post.linked_poll);
+      new_pending_post.shared_from = post.shared_from;
+      let new_pending_post_id = self._insert_pending_post(new_pending_post)?;
+
+      if let Some(tags) = self.pending_post_tags.take(rejected_id) {
+        self.pending_post_tags.insert(new_pending_post_id, &tags);
+      }
+      if let Some(source_author) = self.pending_shared_post_authors.take(rejected_id) {
+        self.pending_shared_post_authors.insert(new_pending_post_id, &source_author);
+      }
+      self.resubmission_of.insert(new_pending_post_id, &rejected_id);
+
+      let closed_posts = match post.status {
+        PostStatus::Rejected => &mut self.author_to_rejected_pending_posts,
+        _ => &mut self.author_to_expired_pending_posts,
+      };
+      let mut closed_post_ids = closed_posts.get(caller).unwrap_or_default();
+      closed_post_ids.retain(|&id| id != rejected_id);
+      closed_posts.insert(caller, &closed_post_ids);
+      self.pending_posts.remove(rejected_id);
+
+      if !is_space_owner {
+        self.last_post_at.insert(caller, &self.env().block_timestamp());
+      }
+
+      Ok(new_pending_post_id)
+    }
+
+    /// Rejected or expired pending post a pending post was resubmitted from
+    #[ink(message)]
+    pub fn resubmission_of(&self, pending_post_id: PostId) -> Option<PostId> {
+      self.resubmission_of.get(pending_post_id)
+    }
+
+    /// Size of the review queue, only space owner can see it
+    #[ink(message)]
+    pub fn pending_posts_count(&self) -> PostResult<u32> {
+      self._ensure_space_owner()?;
+
+      Ok(self.pending_post_ids.get_or_default().len() as u32)
     }
 
     #[ink(message)]
@@ -265,7 +790,7 @@ mod posts {
     #[ink(message)]
     #[modifiers(only_active_member)]
     pub fn cancel_pending_post(&mut self, pending_post_id: PostId) -> PostResult<()> {
-      let post = self.pending_posts.get(pending_post_id).ok_or(PostError::PostNotExisted)?;
+      let post = self._get_pending_post(pending_post_id).ok_or(PostError::PostNotExisted)?;
 
       let caller = Self::env().caller();
       if caller != post.author {
@@ -276,6 +801,8 @@ mod posts {
       pending_posts.retain(|&x| x != pending_post_id);
       self.pending_post_ids.set(&pending_posts);
       self.pending_posts.remove(pending_post_id);
+      self.pending_post_tags.remove(pending_post_id);
+      self.pending_shared_post_authors.remove(pending_post_id);
 
       let mut author_to_id = self.author_to_pending_posts.get(caller).unwrap();
       author_to_id.retain(|&id| id != pending_post_id);
@@ -287,40 +814,47 @@ mod posts {
     #[ink(message)]
     #[modifiers(only_active_member)]
     pub fn update_pending_post(&mut self, pending_post_id: PostId, content: PostContent) -> PostResult<()> {
-      let mut post = self.pending_posts.get(pending_post_id).ok_or(PostError::PostNotExisted)?;
+      self._ensure_posting_not_frozen()?;
+      let mut post = self._get_pending_post(pending_post_id).ok_or(PostError::PostNotExisted)?;
+      self._ensure_valid_content(&content)?;
 
       let caller = Self::env().caller();
       if caller != post.author {
         return Err(PluginError::UnAuthorized.into());
       }
 
+      let updated_at = Self::env().block_timestamp();
       post.content = content;
+      post.updated_at = Some(updated_at);
       self.pending_posts.insert(pending_post_id, &post);
 
+      self.env().emit_event(PendingPostUpdated { pending_post_id, updated_at });
+
       Ok(())
     }
 
     #[ink(message)]
-    pub fn list_pinned_posts(&self) -> Vec<PostRecord> {
+    pub fn list_pinned_posts(&self, include_deleted: bool) -> PostResult<Vec<PostRecord>> {
+      self._ensure_read_permission()?;
+      let include_deleted = self._include_deleted(include_deleted);
       let pinned_posts = self.pinned_posts.get_or_default();
 
-      return pinned_posts.iter()
-        .map(|&id| PostRecord {
-          post_id: id,
-          post: self.posts.get(id).unwrap(),
-        })
-        .collect();
+      Ok(
+        pinned_posts.iter()
+          .filter_map(|&id| self._get_post(id, include_deleted).map(|post| self._post_record(id, post)))
+          .collect()
+      )
     }
 
     #[ink(message)]
     #[modifiers(only_space_owner)]
     pub fn pin_post(&mut self, post_id: PostId) -> PostResult<()> {
-      if !self.posts.contains(post_id) {
-        return Err(PostError::PostNotExisted);
-      }
+      let post = self._get_post_by_id(post_id).ok_or(PostError::PostNotExisted)?;
+      ensure!(post.status != PostStatus::Archived, PostError::PostArchived);
 
-      let mut pinned_posts = self.pinned_posts.get_or_default();
+      let mut pinned_posts = self._pinned_post_ids();
       if !pinned_posts.contains(&post_id) {
+        ensure!(pinned_posts.len() < MAX_PINNED_POSTS, PostError::TooManyPinnedPosts);
         pinned_posts.push(post_id);
       }
 
@@ -332,20 +866,81 @@ mod posts {
     #[ink(message)]
     #[modifiers(only_space_owner)]
     pub fn unpin_post(&mut self, post_id: PostId) -> PostResult<()> {
+      let mut pinned_posts = self._pinned_post_ids();
+      pinned_posts.retain(|&id| id != post_id);
+      self.pinned_posts.set(&pinned_posts);
 
+      Ok(())
+    }
+
+    /// Remove pins of deleted posts, returns number of removed pins
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn cleanup_stale_pins(&mut self) -> PostResult<u32> {
       let mut pinned_posts = self.pinned_posts.get_or_default();
-      if pinned_posts.contains(&post_id) {
-        pinned_posts.retain(|&id| id != post_id);
-        self.pinned_posts.set(&pinned_posts);
-      }
+      let pinned_count = pinned_posts.len();
+
+      pinned_posts.retain(|&id| self._get_existing_post(id).is_some());
+      self.pinned_posts.set(&pinned_posts);
+
+      Ok(pinned_count.saturating_sub(pinned_posts.len()) as u32)
+    }
+
+    /// Freeze a post against further edits
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn lock_post(&mut self, post_id: PostId) -> PostResult<()> {
+      self._set_post_locked(post_id, true)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn unlock_post(&mut self, post_id: PostId) -> PostResult<()> {
+      self._set_post_locked(post_id, false)
+    }
+
+    /// Reorder pinned posts, `ordered_ids` must contain exactly the current pinned posts
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn reorder_pinned_posts(&mut self, ordered_ids: Vec<PostId>) -> PostResult<()> {
+      let pinned_posts = self._pinned_post_ids();
+
+      let mut sorted_pinned = pinned_posts.clone();
+      sorted_pinned.sort_unstable();
+      let mut sorted_ordered = ordered_ids.clone();
+      sorted_ordered.sort_unstable();
+      ensure!(sorted_pinned == sorted_ordered, PostError::Custom(String::from("Ordered ids must match the pinned posts")));
+
+      self.pinned_posts.set(&ordered_ids);
 
       Ok(())
     }
 
+    /// Pinned post ids with deleted posts filtered out
+    fn _pinned_post_ids(&self) -> Vec<PostId> {
+      let mut pinned_posts = self.pinned_posts.get_or_default();
+      pinned_posts.retain(|&id| self._get_post_by_id(id).is_some());
+
+      pinned_posts
+    }
+
     #[ink(message)]
     #[modifiers(only_active_member)]
-    pub fn new_comment(&mut self, parent_id: PostId, content: PostContent) -> PostResult<PostId> {
-      let _post = self._get_post_by_id(parent_id).ok_or(PostError::PostNotExisted)?;
+    /// Comment on a post, or reply to a top-level comment of the post if `reply_to` is set
+    pub fn new_comment(&mut self, parent_id: PostId, content: PostContent, reply_to: Option<PostId>) -> PostResult<PostId> {
+      self._ensure_posting_not_frozen()?;
+      let post = self._get_post_by_id(parent_id).ok_or(PostError::PostNotExisted)?;
+      ensure!(post.comments_enabled, PostError::CommentsDisabled);
+      self._ensure_valid_content(&content)?;
+
+      if let Some(comment_id) = reply_to {
+        let parent_comment = self._get_comment_by_id(comment_id).ok_or(PostError::PostNotExisted)?;
+        ensure!(
+          parent_comment.parent_id == Some(parent_id) && parent_comment.status != PostStatus::Deleted,
+          PostError::Custom(String::from("Comment not found on this post"))
+        );
+        ensure!(!self.comment_reply_to.contains(comment_id), PostError::Custom(String::from("Cannot reply to a reply")));
+      }
 
       let author = self.env().caller();
       let new_comment_id = self.comments_nonce.get_or_default();
@@ -356,13 +951,34 @@ mod posts {
         content,
         author,
         created_at: Self::env().block_timestamp(),
-        updated_at: None
+        updated_at: None,
+        approved_at: None,
+        shared_from: None,
+        status: PostStatus::Published,
+        updated_by: None,
+        locked: false,
+        publish_at: None,
+        linked_poll: None,
+        comments_enabled: true,
       };
       self.comments.insert(new_comment_id, &comment);
 
-      let mut comments = self.post_to_comments.get(parent_id).unwrap_or_default();
-      comments.push(new_comment_id);
-      self.post_to_comments.insert(parent_id, &comments);
+      // replies are listed under their comment, not along with top-level comments
+      match reply_to {
+        Some(comment_id) => {
+          self.comment_reply_to.insert(new_comment_id, &comment_id);
+
+          let mut replies = self.comment_replies.get(comment_id).unwrap_or_default();
+          replies.push(new_comment_id);
+          self.comment_replies.insert(comment_id, &replies);
+        }
+        None => {
+          let mut comments = self.post_to_comments.get(parent_id).unwrap_or_default();
+          comments.push(new_comment_id);
+          self.post_to_comments.insert(parent_id, &comments);
+        }
+      }
+      self.comment_counts.insert(parent_id, &self.comments_count(parent_id).saturating_add(1));
 
       self.comments_nonce.set(&next_comment_nonce);
 
@@ -372,7 +988,9 @@ mod posts {
     #[ink(message)]
     #[modifiers(only_active_member)]
     pub fn update_comment(&mut self, id: PostId, content: PostContent) -> PostResult<()> {
-      let mut comment = self.comments.get(id).ok_or(PostError::PostNotExisted)?;
+      let mut comment = self._stored_comment(id).ok_or(PostError::PostNotExisted)?;
+      ensure!(comment.status != PostStatus::Deleted, PostError::PostNotExisted);
+      self._ensure_valid_content(&content)?;
 
       let caller = self.env().caller();
       let space_owner_id = self._space_owner_id();
@@ -383,6 +1001,7 @@ mod posts {
 
       comment.content = content;
       comment.updated_at = Some(Self::env().block_timestamp());
+      comment.updated_by = Some(caller);
 
       self.comments.insert(id, &comment);
 
@@ -392,7 +1011,8 @@ mod posts {
     #[ink(message)]
     #[modifiers(only_active_member)]
     pub fn delete_comment(&mut self, id: PostId) -> PostResult<()> {
-      let comment = self.comments.get(id).ok_or(PostError::PostNotExisted)?;
+      let mut comment = self._stored_comment(id).ok_or(PostError::PostNotExisted)?;
+      ensure!(comment.status != PostStatus::Deleted, PostError::PostNotExisted);
 
       let caller = self.env().caller();
       let space_owner_id = self._space_owner_id();
@@ -401,9 +1021,26 @@ mod posts {
         return Err(PluginError::UnAuthorized.into());
       }
 
-      let mut comments = self.post_to_comments.get(comment.parent_id.unwrap()).unwrap();
-      comments.retain(|comment_id| comment_id != &id);
-      self.post_to_comments.insert(comment.parent_id.unwrap(), &comments);
+      let parent_id = comment.parent_id.unwrap();
+      self.comment_counts.insert(parent_id, &self.comments_count(parent_id).saturating_sub(1));
+
+      if let Some(comment_id) = self.comment_reply_to.get(id) {
+        let mut replies = self.comment_replies.get(comment_id).unwrap_or_default();
+        replies.retain(|reply_id| reply_id != &id);
+        self.comment_replies.insert(comment_id, &replies);
+        self.comment_reply_to.remove(id);
+      } else if self.replies_count(id) > 0 {
+        // keep a tombstone so replies are still rendered under the deleted comment
+        comment.status = PostStatus::Deleted;
+        comment.content = PostContent::Raw(String::new());
+        self.comments.insert(id, &comment);
+
+        return Ok(());
+      } else {
+        let mut comments = self.post_to_comments.get(parent_id).unwrap();
+        comments.retain(|comment_id| comment_id != &id);
+        self.post_to_comments.insert(parent_id, &comments);
+      }
 
       self.comments.remove(id);
 
@@ -417,157 +1054,2218 @@ mod posts {
     }
 
     #[ink(message)]
-    pub fn comments_by_post(&self, parent_id: PostId) -> Vec<PostRecord> {
+    pub fn list_comments(&self, parent_id: PostId, from: u32, per_page: u32) -> PostResult<PostsPage> {
+      self._ensure_read_permission()?;
+
+      let per_page = per_page.min(50); // limit per page at max 50 items
       let comment_ids = self.post_to_comments.get(parent_id).unwrap_or_default();
+      let last_position = from.saturating_add(per_page);
+      let total = comment_ids.len() as u32;
+
+      let page: Option<&[PostId]> = comment_ids.get((from as usize)..(last_position.min(total) as usize));
+      let items = match page {
+        Some(list) => list.iter()
+          .filter_map(|&id| self._get_comment_by_id(id).map(|post| self._post_record(id, post)))
+          .collect(),
+        None => Vec::new()
+      };
 
-      comment_ids.iter().map(|id| PostRecord {post_id: *id, post: self._get_comment_by_id(*id).unwrap()}).collect()
+      Ok(PostsPage {
+        items,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      })
     }
 
     #[ink(message)]
-    #[modifiers(only_active_member)]
-    pub fn update_post(&mut self, id: PostId, content: PostContent) -> PostResult<()> {
-      let mut post = self._get_post_by_id(id).ok_or(PostError::PostNotExisted)?;
-
-      let caller = Self::env().caller();
-      let space_owner_id = self._space_owner_id();
+    pub fn comments_count(&self, parent_id: PostId) -> u32 {
+      // posts commented before the counter existed fall back to the comments list
+      self.comment_counts.get(parent_id)
+        .unwrap_or_else(|| self.post_to_comments.get(parent_id).unwrap_or_default().len() as u32)
+    }
 
-      if !(caller == post.author || caller == space_owner_id) {
-        return Err(PluginError::UnAuthorized.into());
-      }
+    #[ink(message)]
+    pub fn replies_count(&self, comment_id: PostId) -> u32 {
+      self.comment_replies.get(comment_id).unwrap_or_default().len() as u32
+    }
 
-      post.content = content;
-      post.updated_at = Some(Self::env().block_timestamp());
+    #[ink(message)]
+    pub fn list_replies(&self, post_id: PostId, comment_id: PostId, from: u32, per_page: u32) -> PostResult<PostsPage> {
+      self._ensure_read_permission()?;
 
-      self.posts.insert(id, &post);
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let reply_ids = match self._get_comment_by_id(comment_id) {
+        Some(comment) if comment.parent_id == Some(post_id) => self.comment_replies.get(comment_id).unwrap_or_default(),
+        _ => Vec::new()
+      };
+      let last_position = from.saturating_add(per_page);
+      let total = reply_ids.len() as u32;
+
+      let page: Option<&[PostId]> = reply_ids.get((from as usize)..(last_position.min(total) as usize));
+      let items = match page {
+        Some(list) => list.iter()
+          .filter_map(|&id| self._get_comment_by_id(id).map(|post| self._post_record(id, post)))
+          .collect(),
+        None => Vec::new()
+      };
+
+      Ok(PostsPage {
+        items,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      })
+    }
+
+    /// Comments count of posts, at max 50 posts
+    #[ink(message)]
+    pub fn comments_counts(&self, post_ids: Vec<PostId>) -> Vec<(PostId, u32)> {
+      post_ids.iter()
+        .take(50)
+        .map(|&id| (id, self.comments_count(id)))
+        .collect()
+    }
+
+    #[ink(message)]
+    pub fn comments_by_post(&self, parent_id: PostId) -> PostResult<Vec<PostRecord>> {
+      self._ensure_read_permission()?;
+      let comment_ids = self.post_to_comments.get(parent_id).unwrap_or_default();
+
+      Ok(comment_ids.iter()
+        .filter_map(|&id| self._get_comment_by_id(id).map(|comment| (id, comment)))
+        .filter(|(_, comment)| comment.status != PostStatus::Deleted)
+        .map(|(id, comment)| self._post_record(id, comment))
+        .collect())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn update_post(&mut self, id: PostId, content: PostContent, tags: Vec<String>, linked_poll: Option<u32>) -> PostResult<()> {
+      self._ensure_posting_not_frozen()?;
+      let mut post = self._get_existing_post(id).ok_or(PostError::PostNotExisted)?;
+      ensure!(!post.locked, PostError::PostLocked);
+      self._ensure_valid_content(&content)?;
+      let tags = Self::_normalize_tags(tags)?;
+      self._ensure_valid_linked_poll(linked_poll)?;
+
+      let caller = Self::env().caller();
+      let space_owner_id = self._space_owner_id();
+
+      if !(caller == post.author || caller == space_owner_id) {
+        return Err(PluginError::UnAuthorized.into());
+      }
+
+      let edited_at = Self::env().block_timestamp();
+      self._add_post_revision(id, PostRevision {
+        content: post.content,
+        edited_by: caller,
+        edited_at,
+      });
+
+      post.content = content;
+      post.updated_at = Some(edited_at);
+      post.updated_by = Some(caller);
+      post.linked_poll = linked_poll;
+
+      self.posts.insert(id, &post);
+      self._update_post_tags(id, tags)?;
+
+      Ok(())
+    }
+
+    /// List kept revisions of a post, from oldest to newest
+    #[ink(message)]
+    pub fn post_revisions(&self, post_id: PostId, from: u32, per_page: u32) -> PostResult<PostRevisionsPage> {
+      self._ensure_read_permission()?;
+
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let revisions_count = self.post_revisions_count.get(post_id).unwrap_or_default();
+      let first_revision = revisions_count.saturating_sub(MAX_POST_REVISIONS);
+      let total = revisions_count.saturating_sub(first_revision);
+      let last_position = from.saturating_add(per_page);
+
+      let items = ((from as usize)..(last_position.min(total) as usize))
+        .filter_map(|index| self.post_revisions.get((post_id, first_revision.saturating_add(index as u32))))
+        .collect();
+
+      Ok(PostRevisionsPage {
+        items,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      })
+    }
+
+    fn _add_post_revision(&mut self, post_id: PostId, revision: PostRevision) {
+      let revision_id = self.post_revisions_count.get(post_id).unwrap_or_default();
+      self.post_revisions.insert((post_id, revision_id), &revision);
+
+      if revision_id >= MAX_POST_REVISIONS {
+        self.post_revisions.remove((post_id, revision_id - MAX_POST_REVISIONS));
+      }
+
+      self.post_revisions_count.insert(post_id, &revision_id.saturating_add(1));
+    }
+
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn delete_post(&mut self, id: PostId) -> PostResult<()> {
+      let mut post = self._get_existing_post(id).ok_or(PostError::PostNotExisted)?;
+      ensure!(!post.locked, PostError::PostLocked);
+
+      let caller = Self::env().caller();
+      let space_owner_id = self._space_owner_id();
+
+      if caller != post.author && caller != space_owner_id {
+        return Err(PluginError::UnAuthorized.into());
+      }
+
+      post.status = PostStatus::Deleted;
+      self.posts.insert(id, &post);
+      self.active_posts_count.set(&self.active_posts_count.get_or_default().saturating_sub(1));
+      self._update_post_tags(id, Vec::new())?;
+      self._clear_reports(id);
+
+      let mut author_posts = self.author_to_posts.get(post.author).unwrap_or_default();
+      author_posts.retain(|&post_id| post_id != id);
+      self.author_to_posts.insert(post.author, &author_posts);
+
+      let count = self.author_post_counts.get(post.author).unwrap_or_default().saturating_sub(1);
+      self.author_post_counts.insert(post.author, &count);
+
+      self._remove_pinned_post(id);
 
       Ok(())
     }
 
+    /// Hide a post from listings, it's still reachable by id
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn archive_post(&mut self, post_id: PostId) -> PostResult<()> {
+      self._set_post_archived(post_id, true)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn unarchive_post(&mut self, post_id: PostId) -> PostResult<()> {
+      self._set_post_archived(post_id, false)
+    }
+
     #[ink(message)]
-    pub fn list_posts(&self, from: u32, per_page: u32, ordering: Ordering) -> PostsPage {
+    pub fn list_posts(&self, from: u32, per_page: u32, ordering: Ordering, include_deleted: bool, include_archived: bool) -> PostResult<PostsWithStatsPage> {
+      self._ensure_read_permission()?;
+      let include_deleted = self._include_deleted(include_deleted);
+      let include_archived = self._include_deleted(include_archived);
+
       match ordering {
-        Ordering::Ascending => panic!("Not supported"),
+        Ordering::Ascending => {
+          let per_page = per_page.min(50); // limit per page at max 50 items
+          let current_posts_nonce = self.posts_nonce.get_or_default();
+          let last_position = from.saturating_add(per_page);
+
+          let mut post_records = Vec::new();
+          for index in (from as usize)..(last_position.min(current_posts_nonce) as usize) {
+            let bounded_index = index as u32;
+
+            if let Some(post) = self._get_post(bounded_index, include_deleted) {
+              if include_archived || post.status != PostStatus::Archived {
+                post_records.push(self._post_record_with_stats(bounded_index, post));
+              }
+            }
+          }
+
+          Ok(PostsWithStatsPage {
+            items: post_records,
+            from,
+            per_page,
+            has_next_page: last_position < current_posts_nonce,
+            total: self.active_posts_count.get_or_default(),
+          })
+        }
         Ordering::Descending => {
           let per_page = per_page.min(50); // limit per page at max 50 items
           let current_posts_nonce = self.posts_nonce.get_or_default();
-          let bounded_from = from.saturating_add(1);
+          // `from = u32::MAX` starts from the newest post
+          let bounded_from = from.saturating_add(1).min(current_posts_nonce);
           let last_position = bounded_from.saturating_sub(per_page);
 
           let mut post_records = Vec::new();
-          for index in ((last_position as usize)..(bounded_from.min(current_posts_nonce) as usize)).rev() {
+          for index in ((last_position as usize)..(bounded_from as usize)).rev() {
             let bounded_index = index as u32;
 
-            if let Some(post) = self.posts.get(bounded_index) {
-              post_records.push(PostRecord { post_id: bounded_index, post });
+            if let Some(post) = self._get_post(bounded_index, include_deleted) {
+              if include_archived || post.status != PostStatus::Archived {
+                post_records.push(self._post_record_with_stats(bounded_index, post));
+              }
             }
           }
 
-          PostsPage {
+          Ok(PostsWithStatsPage {
             items: post_records,
             from,
             per_page,
             has_next_page: last_position > 0,
-            total: current_posts_nonce,
-          }
+            total: self.active_posts_count.get_or_default(),
+          })
+        }
+      }
+    }
+
+    /// List posts along with display names of their authors
+    #[ink(message)]
+    pub fn list_posts_with_authors(&self, from: u32, per_page: u32, ordering: Ordering) -> PostResult<PostsWithAuthorsPage> {
+      let page = self.list_posts(from, per_page, ordering, false, false)?;
+
+      let mut authors: Vec<AccountId> = Vec::new();
+      for record in page.items.iter() {
+        if !authors.contains(&record.post.author) {
+          authors.push(record.post.author);
         }
       }
+
+      // names are left empty if the space cannot be reached
+      let author_names = build_call::<DefaultEnvironment>()
+        .call(self._space_id())
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("member_names")))
+            .push_arg(authors)
+        )
+        .returns::<Vec<(AccountId, Option<String>)>>()
+        .try_invoke()
+        .ok()
+        .and_then(|rs| rs.ok())
+        .unwrap_or_default();
+
+      let items = page.items.into_iter()
+        .map(|record| {
+          let author_name = author_names.iter()
+            .find(|(account_id, _)| *account_id == record.post.author)
+            .and_then(|(_, name)| name.clone());
+
+          PostRecordWithAuthor { post_id: record.post_id, post: record.post, author_name }
+        })
+        .collect();
+
+      Ok(PostsWithAuthorsPage {
+        items,
+        from: page.from,
+        per_page: page.per_page,
+        has_next_page: page.has_next_page,
+        total: page.total,
+      })
     }
 
+    /// Share a post from another space's posts plugin, the original author is kept.
+    /// Shared posts go through the same checks & review queue as new posts
     #[ink(message)]
-    pub fn post_by_id(&self, id: PostId) -> Option<Post> {
-      self._get_post_by_id(id)
+    pub fn share_post(&mut self, source_space_posts_plugin: AccountId, source_post_id: PostId) -> PostResult<(PostCreationStatus, PostId)> {
+      ensure!(source_space_posts_plugin != self.env().account_id(), PostError::Custom(String::from("Cannot share post from the same space")));
+
+      let caller = Self::env().caller();
+      let is_space_owner = self._ensure_can_create_post(caller)?;
+
+      // the source might not be a compatible posts plugin, fail instead of trapping on its reply
+      let source_post = build_call::<DefaultEnvironment>()
+        .call(source_space_posts_plugin)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("post_by_id")))
+            .push_arg(source_post_id)
+        )
+        .returns::<PostResult<Option<PostRecord>>>()
+        .try_invoke()
+        .ok()
+        .and_then(|rs| rs.ok())
+        .ok_or(PostError::Custom(String::from("Cannot fetch post from source")))??
+        .ok_or(PostError::PostNotExisted)?
+        .post;
+
+      self._ensure_valid_content(&source_post.content)?;
+
+      let shared_from = Some((source_space_posts_plugin, source_post_id));
+      let (status, id) = if self._requires_approval(is_space_owner) {
+        let mut new_pending_post = self._build_post(caller, source_post.content, None, None);
+        new_pending_post.shared_from = shared_from;
+
+        let pending_post_id = self._insert_pending_post(new_pending_post)?;
+        self.pending_shared_post_authors.insert(pending_post_id, &source_post.author);
+
+        (PostCreationStatus::Pending, pending_post_id)
+      } else {
+        let mut new_post = self._build_post(source_post.author, source_post.content, None, None);
+        new_post.shared_from = shared_from;
+
+        let new_post_id = self._insert_post(new_post);
+        self.shared_posts_count.set(&self.shared_posts_count().saturating_add(1));
+
+        (PostCreationStatus::Created, new_post_id)
+      };
+
+      if !is_space_owner {
+        self.last_post_at.insert(caller, &self.env().block_timestamp());
+      }
+
+      Ok((status, id))
     }
 
     #[ink(message)]
-    pub fn posts_by_ids(&self, ids: Vec<PostId>) -> Vec<(PostId, Option<Post>)> {
-      ids.iter()
-        .map(|&id| (id, self._get_post_by_id(id)))
-        .collect()
+    pub fn is_shared_post(&self, post_id: PostId) -> bool {
+      self._get_post_by_id(post_id).map(|post| post.shared_from.is_some()).unwrap_or_default()
     }
 
     #[ink(message)]
-    pub fn post_perm(&self) -> PostPerm {
-      self.post_perm.get_or_default()
+    pub fn shared_posts_count(&self) -> u32 {
+      self.shared_posts_count.get_or_default()
     }
 
     #[ink(message)]
-    #[modifiers(only_space_owner)]
-    pub fn update_perm(&mut self, new_perm: PostPerm) -> PostResult<()> {
+    #[modifiers(only_active_member)]
+    pub fn bookmark_post(&mut self, post_id: PostId) -> PostResult<()> {
+      ensure!(self._get_post_by_id(post_id).is_some(), PostError::PostNotExisted);
 
-      self.post_perm.set(&new_perm);
+      let caller = self.env().caller();
+      if self.bookmarks.contains((caller, post_id)) {
+        return Ok(());
+      }
+
+      self.bookmarks.insert((caller, post_id), &true);
+
+      let mut member_bookmarks = self.member_bookmarks.get(caller).unwrap_or_default();
+      member_bookmarks.push(post_id);
+      self.member_bookmarks.insert(caller, &member_bookmarks);
+
+      self.post_bookmark_count.insert(post_id, &self.bookmark_count(post_id).saturating_add(1));
 
       Ok(())
     }
 
     #[ink(message)]
-    pub fn posts_count(&self) -> u32 {
-      self.posts_nonce.get_or_default()
-    }
+    #[modifiers(only_active_member)]
+    pub fn unbookmark_post(&mut self, post_id: PostId) -> PostResult<()> {
+      let caller = self.env().caller();
+      if !self.bookmarks.contains((caller, post_id)) {
+        return Ok(());
+      }
 
-    fn _new_post(&mut self, content: PostContent) -> PostResult<PostId> {
-      self._ensure_post_permission()?;
+      self.bookmarks.remove((caller, post_id));
 
-      let caller = self.env().caller();
+      let mut member_bookmarks = self.member_bookmarks.get(caller).unwrap_or_default();
+      member_bookmarks.retain(|&id| id != post_id);
+      self.member_bookmarks.insert(caller, &member_bookmarks);
 
-      let new_post_id = self.posts_nonce.get_or_default();
-      let next_post_nonce = new_post_id.checked_add(1).expect("Exceeds number of posts!");
+      self.post_bookmark_count.insert(post_id, &self.bookmark_count(post_id).saturating_sub(1));
 
-      let new_post = Post {
-        author: caller,
-        content,
-        created_at: Self::env().block_timestamp(),
-        updated_at: None,
-        parent_id: None,
+      Ok(())
+    }
+
+    /// List bookmarked posts of the caller, deleted posts are skipped
+    #[ink(message)]
+    pub fn list_my_bookmarks(&self, from: u32, per_page: u32) -> PostResult<PostsWithStatsPage> {
+      self._ensure_read_permission()?;
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let bookmarked_ids = self.member_bookmarks.get(self.env().caller()).unwrap_or_default();
+      let last_position = from.saturating_add(per_page);
+      let total = bookmarked_ids.len() as u32;
+
+      let page: Option<&[PostId]> = bookmarked_ids.get((from as usize)..(last_position.min(total) as usize));
+      let items = match page {
+        Some(list) => list.iter()
+          .filter_map(|&id| self._get_post_by_id(id).map(|post| self._post_record_with_stats(id, post)))
+          .collect(),
+        None => Vec::new()
       };
 
-      self.posts.insert(new_post_id, &new_post);
-      self.posts_nonce.set(&next_post_nonce);
+      Ok(PostsWithStatsPage {
+        items,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      })
+    }
 
-      Ok(new_post_id)
+    #[ink(message)]
+    pub fn bookmark_count(&self, post_id: PostId) -> u32 {
+      self.post_bookmark_count.get(post_id).unwrap_or_default()
     }
 
-    fn _new_pending_post(&mut self, content: PostContent) -> PostResult<PostId> {
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn react(&mut self, post_id: PostId, kind: ReactionKind) -> PostResult<()> {
+      ensure!(self._get_post_by_id(post_id).is_some(), PostError::PostNotExisted);
+
       let caller = self.env().caller();
+      match self.reactions.get((post_id, caller)) {
+        Some(current) if current == kind => return Ok(()),
+        // replace previous reaction of the member
+        Some(current) => self._decrease_reaction_kind_count(post_id, current),
+        None => {
+          self.reaction_counts.insert(post_id, &self._reactions_count(post_id).saturating_add(1));
+        }
+      }
 
-      // Create a pending post
-      let new_pending_post_id = self.pending_posts_nonce.get_or_default();
-      let next_pending_post_nonce = new_pending_post_id.checked_add(1).expect("Exceeds number of pending posts!");
+      self.reactions.insert((post_id, caller), &kind);
+      let count = self.reaction_kind_count(post_id, kind).saturating_add(1);
+      self.reaction_kind_counts.insert((post_id, kind), &count);
 
-      let new_pending_post = Post {
-        author: caller,
-        content,
-        created_at: Self::env().block_timestamp(),
-        updated_at: None,
-        parent_id: None,
-      };
+      Ok(())
+    }
 
-      self.pending_posts.insert(new_pending_post_id, &new_pending_post);
-      self.author_to_pending_posts.insert(caller, &vec![new_pending_post_id]);
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn unreact(&mut self, post_id: PostId) -> PostResult<()> {
+      let caller = self.env().caller();
+      let kind = self.reactions.get((post_id, caller)).ok_or(PostError::ReactionNotFound)?;
 
-      let mut pending_posts = self.pending_post_ids.get_or_default();
-      pending_posts.push(new_pending_post_id);
+      self.reactions.remove((post_id, caller));
+      self.reaction_counts.insert(post_id, &self._reactions_count(post_id).saturating_sub(1));
+      self._decrease_reaction_kind_count(post_id, kind);
 
-      self.pending_post_ids.set(&pending_posts);
-      self.pending_posts_nonce.set(&next_pending_post_nonce);
+      Ok(())
+    }
 
-      Ok(new_pending_post_id)
+    #[ink(message)]
+    pub fn reactions_count(&self, post_id: PostId) -> PostResult<u32> {
+      self._ensure_read_permission()?;
+
+      Ok(self._reactions_count(post_id))
     }
 
-    fn _get_comment_by_id(&self, id: PostId) -> Option<Post> {
-      self.comments.get(id)
+    /// Reaction counts of posts, at max 50 posts
+    #[ink(message)]
+    pub fn reactions_counts(&self, post_ids: Vec<PostId>) -> PostResult<Vec<(PostId, u32)>> {
+      self._ensure_read_permission()?;
+
+      Ok(
+        post_ids.iter()
+          .take(50)
+          .map(|&id| (id, self._reactions_count(id)))
+          .collect()
+      )
     }
 
-    fn _get_post_by_id(&self, id: PostId) -> Option<Post> {
-      self.posts.get(id)
+    #[ink(message)]
+    pub fn has_reacted(&self, post_id: PostId, who: AccountId) -> bool {
+      self.reactions.contains((post_id, who))
     }
 
-    fn _ensure_post_permission(&self) -> PostResult<()> {
-      let permission = self.post_perm();
+    #[ink(message)]
+    pub fn reaction_of(&self, post_id: PostId, who: AccountId) -> Option<ReactionKind> {
+      self.reactions.get((post_id, who))
+    }
 
-      match permission {
-        PostPerm::SpaceOwner => Ok(self._ensure_space_owner()?),
-        PostPerm::ActiveMember | PostPerm::ActiveMemberWithApproval => Ok(self._ensure_active_member()?),
+    #[ink(message)]
+    pub fn reaction_kind_count(&self, post_id: PostId, kind: ReactionKind) -> u32 {
+      self.reaction_kind_counts.get((post_id, kind)).unwrap_or_default()
+    }
+
+    /// Count of each reaction kind a post received, kinds without any reaction are left out
+    #[ink(message)]
+    pub fn reactions_summary(&self, post_id: PostId) -> PostResult<Vec<(ReactionKind, u32)>> {
+      self._ensure_read_permission()?;
+
+      Ok(self._reactions_summary(post_id))
+    }
+
+    /// Reactions summary of posts, at max 50 posts
+    #[ink(message)]
+    pub fn reactions_summaries(&self, post_ids: Vec<PostId>) -> PostResult<Vec<(PostId, Vec<(ReactionKind, u32)>)>> {
+      self._ensure_read_permission()?;
+
+      Ok(
+        post_ids.iter()
+          .take(50)
+          .map(|&id| (id, self._reactions_summary(id)))
+          .collect()
+      )
+    }
+
+    /// Tip the author of a post with the transferred value
+    #[ink(message, payable)]
+    #[modifiers(only_active_member)]
+    pub fn tip_author(&mut self, post_id: PostId) -> PostResult<()> {
+      let post = self._get_post_by_id(post_id).ok_or(PostError::PostNotExisted)?;
+      let tipper = self.env().caller();
+      let amount = self.env().transferred_value();
+      ensure!(post.author != tipper, PostError::CannotTipOwnPost);
+      ensure!(amount > 0, PostError::Custom(String::from("Tip amount must be greater than 0")));
+
+      // keep the tip for the author to claim if the transfer fails
+      if self.env().transfer(post.author, amount).is_err() {
+        let unclaimed = self.unclaimed_tips(post.author).saturating_add(amount);
+        self.unclaimed_tips.insert(post.author, &unclaimed);
+      }
+
+      self.tips_total.insert(post_id, &self.tips_total(post_id).saturating_add(amount));
+      if !self.post_tippers.contains((post_id, tipper)) {
+        self.post_tippers.insert((post_id, tipper), &true);
+        self.tippers_count.insert(post_id, &self.tippers_count(post_id).saturating_add(1));
+      }
+
+      self.env().emit_event(PostTipped { post_id, from: tipper, amount });
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn claim_tips(&mut self) -> PostResult<()> {
+      let author = self.env().caller();
+      let amount = self.unclaimed_tips(author);
+      ensure!(amount > 0, PostError::NoTipsToClaim);
+
+      self.unclaimed_tips.remove(author);
+      if self.env().transfer(author, amount).is_err() {
+        return Err(PostError::CannotTransferTips);
+      }
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn tips_total(&self, post_id: PostId) -> Balance {
+      self.tips_total.get(post_id).unwrap_or_default()
+    }
+
+    #[ink(message)]
+    pub fn tippers_count(&self, post_id: PostId) -> u32 {
+      self.tippers_count.get(post_id).unwrap_or_default()
+    }
+
+    #[ink(message)]
+    pub fn unclaimed_tips(&self, who: AccountId) -> Balance {
+      self.unclaimed_tips.get(who).unwrap_or_default()
+    }
+
+    /// Flag a post for space owner's attention
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn report_post(&mut self, post_id: PostId, reason: String) -> PostResult<()> {
+      ensure!(self._get_post_by_id(post_id).is_some(), PostError::PostNotExisted);
+      ensure!(reason.len() <= MAX_REPORT_REASON_LEN, PostError::Custom(String::from("Report reason is at max 200 chars")));
+
+      let caller = self.env().caller();
+      ensure!(!self.reports.contains((post_id, caller)), PostError::AlreadyReported);
+
+      self.reports.insert((post_id, caller), &reason);
+
+      let mut reporters = self.post_reporters.get(post_id).unwrap_or_default();
+      reporters.push(caller);
+      self.post_reporters.insert(post_id, &reporters);
+
+      let mut reported_post_ids = self.reported_post_ids.get_or_default();
+      if !reported_post_ids.contains(&post_id) {
+        reported_post_ids.push(post_id);
+        self.reported_post_ids.set(&reported_post_ids);
       }
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn report_count(&self, post_id: PostId) -> u32 {
+      self.post_reporters.get(post_id).unwrap_or_default().len() as u32
+    }
+
+    /// List reported posts, most reported first
+    #[ink(message)]
+    pub fn list_reported_posts(&self, from: u32, per_page: u32) -> PostResult<ReportedPostsPage> {
+      self._ensure_space_owner()?;
+
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let mut records: Vec<ReportedPostRecord> = self.reported_post_ids.get_or_default()
+        .iter()
+        .filter_map(|&post_id| self._get_existing_post(post_id).map(|post| ReportedPostRecord {
+          post_id,
+          post,
+          report_count: self.report_count(post_id),
+        }))
+        .collect();
+      records.sort_by(|a, b| b.report_count.cmp(&a.report_count));
+
+      let total = records.len() as u32;
+      let last_position = from.saturating_add(per_page);
+      let items = records.into_iter()
+        .skip(from as usize)
+        .take(per_page as usize)
+        .collect();
+
+      Ok(ReportedPostsPage {
+        items,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      })
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn dismiss_reports(&mut self, post_id: PostId) -> PostResult<()> {
+      self._clear_reports(post_id);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn posts_by_author(&self, who: AccountId, from: u32, per_page: u32, ordering: Ordering) -> PostResult<PostsWithStatsPage> {
+      self._ensure_read_permission()?;
+
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let mut post_ids = self.author_to_posts.get(who).unwrap_or_default();
+      if let Ordering::Descending = ordering {
+        post_ids.reverse();
+      }
+
+      let last_position = from.saturating_add(per_page);
+      let total = post_ids.len() as u32;
+
+      let page: Option<&[PostId]> = post_ids.get((from as usize)..(last_position.min(total) as usize));
+      let items = match page {
+        Some(list) => list.iter()
+          .filter_map(|&id| self._get_post_by_id(id).map(|post| self._post_record_with_stats(id, post)))
+          .collect(),
+        None => Vec::new()
+      };
+
+      Ok(PostsWithStatsPage {
+        items,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      })
+    }
+
+    /// Number of published posts of an author, deleted posts are not counted
+    #[ink(message)]
+    pub fn published_posts_count(&self, who: AccountId) -> u32 {
+      self.author_post_counts.get(who).unwrap_or_default()
+    }
+
+    /// Record a view of the caller on a post, each member is counted once
+    #[ink(message)]
+    #[modifiers(only_active_member)]
+    pub fn record_post_view(&mut self, post_id: PostId) -> PostResult<()> {
+      ensure!(self._get_post_by_id(post_id).is_some(), PostError::PostNotExisted);
+
+      let caller = self.env().caller();
+      if self.viewed_by.contains((post_id, caller)) {
+        return Ok(());
+      }
+
+      self.viewed_by.insert((post_id, caller), &true);
+      self.view_counts.insert(post_id, &self.post_view_count(post_id).saturating_add(1));
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn post_view_count(&self, post_id: PostId) -> u32 {
+      self.view_counts.get(post_id).unwrap_or_default()
+    }
+
+    #[ink(message)]
+    pub fn post_with_stats_by_id(&self, id: PostId) -> PostResult<Option<PostRecordWithStats>> {
+      self._ensure_read_permission()?;
+
+      Ok(self._get_post_by_id(id).map(|post| self._post_record_with_stats(id, post)))
+    }
+
+    #[ink(message)]
+    pub fn post_by_id(&self, id: PostId) -> PostResult<Option<PostRecord>> {
+      self._ensure_read_permission()?;
+
+      Ok(self._get_post_by_id(id).map(|post| self._post_record(id, post)))
+    }
+
+    #[ink(message)]
+    pub fn posts_by_ids(&self, ids: Vec<PostId>, include_deleted: bool) -> PostResult<Vec<(PostId, Option<Post>)>> {
+      self._ensure_read_permission()?;
+      let include_deleted = self._include_deleted(include_deleted);
+
+      Ok(
+        ids.iter()
+          .map(|&id| (id, self._get_post(id, include_deleted)))
+          .collect()
+      )
+    }
+
+    #[ink(message)]
+    pub fn tags_of(&self, post_id: PostId) -> PostResult<Vec<String>> {
+      self._ensure_read_permission()?;
+
+      Ok(self.post_tags.get(post_id).unwrap_or_default())
+    }
+
+    /// List posts of a tag, archived posts are only included for the space owner
+    #[ink(message)]
+    pub fn list_posts_by_tag(&self, tag: String, from: u32, per_page: u32, ordering: Ordering, include_archived: bool) -> PostResult<PostsPage> {
+      self._ensure_read_permission()?;
+      let include_archived = self._include_deleted(include_archived);
+
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let tag_posts = self.tag_to_posts.get(tag.to_lowercase().into_bytes()).unwrap_or_default();
+      let total = tag_posts.len() as u32;
+
+      let (positions, has_next_page): (Vec<u32>, bool) = match ordering {
+        Ordering::Ascending => {
+          let last_position = from.saturating_add(per_page);
+          ((from..last_position.min(total)).collect(), last_position < total)
+        }
+        Ordering::Descending => {
+          // `from = u32::MAX` starts from the newest post
+          let bounded_from = from.saturating_add(1).min(total);
+          let last_position = bounded_from.saturating_sub(per_page);
+          ((last_position..bounded_from).rev().collect(), last_position > 0)
+        }
+      };
+
+      let items = positions.iter()
+        .filter_map(|&position| {
+          let post_id = tag_posts[position as usize];
+          self._get_post_by_id(post_id)
+            .filter(|post| include_archived || post.status != PostStatus::Archived)
+            .map(|post| self._post_record(post_id, post))
+        })
+        .collect();
+
+      Ok(PostsPage {
+        items,
+        from,
+        per_page,
+        has_next_page,
+        total,
+      })
+    }
+
+    /// Most used tags with their number of posts, at most 50 tags
+    #[ink(message)]
+    pub fn popular_tags(&self, top_n: u32) -> Vec<(String, u32)> {
+      let mut tags: Vec<(String, u32)> = self.all_tags.get_or_default()
+        .into_iter()
+        .map(|tag| {
+          let count = self.tag_post_counts.get(tag.as_bytes().to_vec()).unwrap_or_default();
+          (tag, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect();
+
+      tags.sort_by(|a, b| b.1.cmp(&a.1));
+      tags.truncate(top_n.min(50) as usize);
+
+      tags
+    }
+
+    /// Scheduled posts waiting to be published, space owner can see all, others only see their own
+    #[ink(message)]
+    pub fn list_scheduled_posts(&self) -> PostResult<Vec<PostRecord>> {
+      self._ensure_read_permission()?;
+      let caller = self.env().caller();
+      let is_space_owner = caller == self._space_owner_id();
+
+      Ok(
+        self.scheduled_post_ids.get_or_default()
+          .iter()
+          .filter_map(|&id| self._get_existing_post(id).map(|post| self._post_record(id, post)))
+          .filter(|record| !self._is_published(&record.post) && (is_space_owner || record.post.author == caller))
+          .collect()
+      )
+    }
+
+    #[ink(message)]
+    pub fn post_perm(&self) -> PostPerm {
+      self.post_perm.get_or_default()
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn update_perm(&mut self, new_perm: PostPerm) -> PostResult<()> {
+
+      self.post_perm.set(&new_perm);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn read_perm(&self) -> ReadPerm {
+      self.read_perm.get_or_default()
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn update_read_perm(&mut self, new_perm: ReadPerm) -> PostResult<()> {
+      self.read_perm.set(&new_perm);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn is_posting_frozen(&self) -> bool {
+      self.posting_frozen.get_or_default()
+    }
+
+    /// Stop new posts and comments, queued pending posts can still be approved
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn freeze_posting(&mut self) -> PostResult<()> {
+      ensure!(!self.is_posting_frozen(), PostError::PostingFrozen);
+
+      self.posting_frozen.set(&true);
+      self.env().emit_event(PostingFrozen {});
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn unfreeze_posting(&mut self) -> PostResult<()> {
+      ensure!(self.is_posting_frozen(), PostError::Custom(String::from("Posting is not frozen")));
+
+      self.posting_frozen.set(&false);
+      self.env().emit_event(PostingUnfrozen {});
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn polls_plugin_id(&self) -> Option<PluginId> {
+      self.polls_plugin_id.get_or_default()
+    }
+
+    /// Address of the polls plugin, resolved from the space's plugins, None if it's not installed or disabled
+    #[ink(message)]
+    pub fn polls_id(&self) -> Option<AccountId> {
+      self._resolve_polls_id().ok().flatten()
+    }
+
+    /// Link the polls plugin of the space, members have to vote on its open mandatory polls before posting
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_polls_plugin_id(&mut self, plugin_id: Option<PluginId>) -> PostResult<()> {
+      self.polls_plugin_id.set(&plugin_id);
+
+      Ok(())
+    }
+
+    /// Maximum length in bytes of raw post content
+    #[ink(message)]
+    pub fn max_content_len(&self) -> u32 {
+      self.max_content_len.get().unwrap_or(DEFAULT_MAX_CONTENT_LEN)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_max_content_len(&mut self, max_len: u32) -> PostResult<()> {
+      ensure!(max_len <= MAX_CONTENT_LEN_CEILING, PostError::Custom(String::from("Content length is at max 20000 bytes")));
+
+      self.max_content_len.set(&max_len);
+
+      Ok(())
+    }
+
+    /// Whether comments are enabled on newly created posts, enabled by default
+    #[ink(message)]
+    pub fn comments_default_enabled(&self) -> bool {
+      self.comments_default_enabled.get().unwrap_or(true)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_space_owner)]
+    pub fn set_comments_default_enabled(&mut self, enabled: bool) -> PostResult<()> {
+      self.comments_default_enabled.set(&enabled);
+
+      Ok(())
+    }
+
+    /// Open or close comments on a post, only post author or space owner can do this
+    #[ink(message)]
+    pub fn set_comments_enabled(&mut self, post_id: PostId, enabled: bool) -> PostResult<()> {
+      let mut post = self._get_existing_post(post_id).ok_or(PostError::PostNotExisted)?;
+
+      let caller = self.env().caller();
+      if caller != post.author && caller != self._space_owner_id() {
+        return Err(PluginError::UnAuthorized.into());
+      }
+
+      post.comments_enabled = enabled;
+      self.posts.insert(post_id, &post);
+
+      Ok(())
+    }
+
+    /// Number of posts which are not deleted
+    #[ink(message)]
+    pub fn posts_count(&self) -> u32 {
+      self.active_posts_count.get_or_default()
+    }
+
+    /// Number of posts ever created, including deleted ones
+    #[ink(message)]
+    pub fn posts_ever_created(&self) -> u32 {
+      self.posts_nonce.get_or_default()
+    }
+
+    #[ink(message)]
+    pub fn deleted_posts_count(&self) -> u32 {
+      self.posts_ever_created().saturating_sub(self.posts_count())
+    }
+
+    fn _increase_active_posts_count(&mut self) {
+      let count = self.active_posts_count.get_or_default().checked_add(1).expect("Exceeds number of posts!");
+      self.active_posts_count.set(&count);
+    }
+
+    fn _new_post(&mut self, content: PostContent, publish_at: Option<Timestamp>, linked_poll: Option<u32>) -> PostResult<PostId> {
+      self._ensure_post_permission()?;
+
+      let new_post = self._build_post(self.env().caller(), content, publish_at, linked_poll);
+
+      Ok(self._insert_post(new_post))
+    }
+
+    fn _new_pending_post(&mut self, content: PostContent, publish_at: Option<Timestamp>, linked_poll: Option<u32>) -> PostResult<PostId> {
+      let new_pending_post = self._build_post(self.env().caller(), content, publish_at, linked_poll);
+
+      self._insert_pending_post(new_pending_post)
+    }
+
+    fn _build_post(&self, author: AccountId, content: PostContent, publish_at: Option<Timestamp>, linked_poll: Option<u32>) -> Post {
+      Post {
+        author,
+        content,
+        created_at: Self::env().block_timestamp(),
+        updated_at: None,
+        approved_at: None,
+        parent_id: None,
+        shared_from: None,
+        status: PostStatus::Published,
+        updated_by: None,
+        locked: false,
+        publish_at,
+        linked_poll,
+        comments_enabled: self.comments_default_enabled(),
+      }
+    }
+
+    fn _insert_post(&mut self, new_post: Post) -> PostId {
+      let new_post_id = self.posts_nonce.get_or_default();
+      let next_post_nonce = new_post_id.checked_add(1).expect("Exceeds number of posts!");
+
+      self.posts.insert(new_post_id, &new_post);
+      self.posts_nonce.set(&next_post_nonce);
+      self._increase_active_posts_count();
+      self._add_author_post(new_post.author, new_post_id);
+
+      if new_post.publish_at.is_some() {
+        let mut scheduled_post_ids = self.scheduled_post_ids.get_or_default();
+        scheduled_post_ids.retain(|&id| self._stored_post(id).map_or(false, |post| post.status != PostStatus::Deleted && !self._is_published(&post)));
+        scheduled_post_ids.push(new_post_id);
+        self.scheduled_post_ids.set(&scheduled_post_ids);
+      }
+
+      new_post_id
+    }
+
+    /// Queue a pending post for review, its author is the one submitting it
+    fn _insert_pending_post(&mut self, new_pending_post: Post) -> PostResult<PostId> {
+      let author = new_pending_post.author;
+
+      ensure!((self.pending_post_ids.get_or_default().len() as u32) < self.pending_queue_cap(), PostError::QueueFull);
+
+      let limit = self.pending_post_limit();
+      ensure!(self.pending_posts_count_by_author(Some(author)) < limit, PostError::PendingPostLimitExceeded(limit));
+
+      // Create a pending post
+      let new_pending_post_id = self.pending_posts_nonce.get_or_default();
+      let next_pending_post_nonce = new_pending_post_id.checked_add(1).expect("Exceeds number of pending posts!");
+
+      self.pending_posts.insert(new_pending_post_id, &new_pending_post);
+      let mut author_pending_posts = self.author_to_pending_posts.get(author).unwrap_or_default();
+      author_pending_posts.push(new_pending_post_id);
+      self.author_to_pending_posts.insert(author, &author_pending_posts);
+
+      let mut pending_posts = self.pending_post_ids.get_or_default();
+      pending_posts.push(new_pending_post_id);
+
+      self.pending_post_ids.set(&pending_posts);
+      self.pending_posts_nonce.set(&next_pending_post_nonce);
+
+      Ok(new_pending_post_id)
+    }
+
+    /// Checks shared by every way of creating a post, returns whether the caller is the space owner
+    fn _ensure_can_create_post(&self, caller: AccountId) -> PostResult<bool> {
+      self._ensure_posting_not_frozen()?;
+      self._ensure_post_permission()?;
+
+      if let PostPerm::ActiveMember = self.post_perm() {
+        self._ensure_no_pending_mandatory_vote(caller)?;
+      }
+
+      let is_space_owner = caller == self._space_owner_id();
+      if !is_space_owner {
+        self._ensure_post_cooldown_passed(caller)?;
+      }
+
+      Ok(is_space_owner)
+    }
+
+    fn _ensure_post_cooldown_passed(&self, author: AccountId) -> PostResult<()> {
+      if let Some(retry_after) = self.next_post_available_at(author) {
+        ensure!(self.env().block_timestamp() >= retry_after, PostError::CooldownActive(retry_after));
+      }
+
+      Ok(())
+    }
+
+    fn _requires_approval(&self, is_space_owner: bool) -> bool {
+      matches!(self.post_perm(), PostPerm::ActiveMemberWithApproval) && !is_space_owner
+    }
+
+    fn _clear_reports(&mut self, post_id: PostId) {
+      for reporter in self.post_reporters.get(post_id).unwrap_or_default() {
+        self.reports.remove((post_id, reporter));
+      }
+      self.post_reporters.remove(post_id);
+
+      let mut reported_post_ids = self.reported_post_ids.get_or_default();
+      if reported_post_ids.contains(&post_id) {
+        reported_post_ids.retain(|&id| id != post_id);
+        self.reported_post_ids.set(&reported_post_ids);
+      }
+    }
+
+    fn _normalize_tags(tags: Vec<String>) -> PostResult<Vec<String>> {
+      ensure!(tags.len() <= MAX_TAGS_PER_POST, PostError::InvalidTags);
+
+      let mut normalized_tags: Vec<String> = Vec::new();
+      for tag in tags {
+        let tag = tag.to_lowercase();
+        ensure!(!tag.is_empty() && tag.chars().count() <= MAX_TAG_LEN, PostError::InvalidTags);
+
+        if !normalized_tags.contains(&tag) {
+          normalized_tags.push(tag);
+        }
+      }
+
+      Ok(normalized_tags)
+    }
+
+    /// Replace tags of a post & keep the tag indexes in sync
+    fn _update_post_tags(&mut self, post_id: PostId, new_tags: Vec<String>) -> PostResult<()> {
+      let old_tags = self.post_tags.get(post_id).unwrap_or_default();
+      let mut all_tags = self.all_tags.get_or_default();
+
+      for tag in old_tags.iter().filter(|tag| !new_tags.contains(tag)) {
+        let key = tag.as_bytes().to_vec();
+
+        let mut tag_posts = self.tag_to_posts.get(&key).unwrap_or_default();
+        tag_posts.retain(|&id| id != post_id);
+
+        let count = self.tag_post_counts.get(&key).unwrap_or_default().saturating_sub(1);
+        if count == 0 && tag_posts.is_empty() {
+          self.tag_to_posts.remove(&key);
+          self.tag_post_counts.remove(&key);
+          all_tags.retain(|existing_tag| existing_tag != tag);
+        } else {
+          self.tag_to_posts.insert(&key, &tag_posts);
+          self.tag_post_counts.insert(&key, &count);
+        }
+      }
+
+      for tag in new_tags.iter().filter(|tag| !old_tags.contains(tag)) {
+        let key = tag.as_bytes().to_vec();
+
+        let mut tag_posts = self.tag_to_posts.get(&key).unwrap_or_default();
+        tag_posts.push(post_id);
+        self.tag_to_posts.insert(&key, &tag_posts);
+
+        let count = self.tag_post_counts.get(&key).unwrap_or_default().saturating_add(1);
+        self.tag_post_counts.insert(&key, &count);
+
+        if !all_tags.contains(tag) {
+          all_tags.push(tag.clone());
+        }
+      }
+
+      ensure!(all_tags.len() <= MAX_DISTINCT_TAGS, PostError::TooManyTags);
+      self.all_tags.set(&all_tags);
+
+      if new_tags.is_empty() {
+        self.post_tags.remove(post_id);
+      } else {
+        self.post_tags.insert(post_id, &new_tags);
+      }
+
+      Ok(())
+    }
+
+    fn _post_record(&self, post_id: PostId, post: Post) -> PostRecord {
+      PostRecord {
+        post_id,
+        post,
+        tips_total: self.tips_total(post_id),
+      }
+    }
+
+    /// Pending posts have their own ids, so they never share stats with published posts
+    fn _pending_post_record(post_id: PostId, post: Post) -> PostRecord {
+      PostRecord {
+        post_id,
+        post,
+        tips_total: 0,
+      }
+    }
+
+    fn _post_record_with_stats(&self, post_id: PostId, post: Post) -> PostRecordWithStats {
+      PostRecordWithStats {
+        post_id,
+        post,
+        view_count: self.post_view_count(post_id),
+        tips_total: self.tips_total(post_id),
+      }
+    }
+
+    fn _add_author_post(&mut self, author: AccountId, post_id: PostId) {
+      let mut author_posts = self.author_to_posts.get(author).unwrap_or_default();
+      author_posts.push(post_id);
+      self.author_to_posts.insert(author, &author_posts);
+
+      let count = self.author_post_counts.get(author).unwrap_or_default().saturating_add(1);
+      self.author_post_counts.insert(author, &count);
+    }
+
+    fn _decrease_reaction_kind_count(&mut self, post_id: PostId, kind: ReactionKind) {
+      let count = self.reaction_kind_count(post_id, kind).saturating_sub(1);
+      self.reaction_kind_counts.insert((post_id, kind), &count);
+    }
+
+    fn _reactions_count(&self, post_id: PostId) -> u32 {
+      self.reaction_counts.get(post_id).unwrap_or_default()
+    }
+
+    fn _reactions_summary(&self, post_id: PostId) -> Vec<(ReactionKind, u32)> {
+      ReactionKind::ALL.iter()
+        .map(|&kind| (kind, self.reaction_kind_count(post_id, kind)))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+    }
+
+    fn _get_comment_by_id(&self, id: PostId) -> Option<Post> {
+      self._stored_comment(id)
+    }
+
+    fn _set_post_locked(&mut self, post_id: PostId, locked: bool) -> PostResult<()> {
+      let mut post = self._get_post_by_id(post_id).ok_or(PostError::PostNotExisted)?;
+
+      post.locked = locked;
+      self.posts.insert(post_id, &post);
+
+      Ok(())
+    }
+
+    /// Space owner can always read posts
+    fn _ensure_read_permission(&self) -> PostResult<()> {
+      match self.read_perm.get_or_default() {
+        ReadPerm::Public => Ok(()),
+        ReadPerm::ActiveMember => {
+          if self.env().caller() == self._space_owner_id() {
+            return Ok(());
+          }
+
+          Ok(self._ensure_active_member()?)
+        }
+      }
+    }
+
+    /// Read a post from one of the posts, comments or pending posts mappings,
+    /// posts stored in the legacy layout are read with defaults for the appended fields
+    fn _load_post(mapping_key: Key, id: PostId) -> Option<Post> {
+      let key = (mapping_key, id);
+      match ink::env::get_contract_storage::<(Key, PostId), Post>(&key) {
+        Ok(maybe_post) => maybe_post,
+        Err(_) => ink::env::get_contract_storage::<(Key, PostId), LegacyPost>(&key).ok().flatten().map(Post::from),
+      }
+    }
+
+    fn _stored_post(&self, id: PostId) -> Option<Post> {
+      Self::_load_post(StorageKey::key(&self.posts), id)
+    }
+
+    fn _stored_comment(&self, id: PostId) -> Option<Post> {
+      Self::_load_post(StorageKey::key(&self.comments), id)
+    }
+
+    fn _stored_pending_post(&self, id: PostId) -> Option<Post> {
+      Self::_load_post(StorageKey::key(&self.pending_posts), id)
+    }
+
+    /// Get a pending post which is still waiting for review
+    fn _get_pending_post(&self, id: PostId) -> Option<Post> {
+      self._stored_pending_post(id).filter(|post| post.status != PostStatus::Expired && post.status != PostStatus::Rejected)
+    }
+
+    /// Keep an expired or rejected pending post for its author, only latest ones are kept
+    fn _keep_closed_pending_post(&mut self, pending_post_id: PostId, mut post: Post, status: PostStatus) {
+      post.status = status;
+      self.pending_posts.insert(pending_post_id, &post);
+
+      let closed_posts = match status {
+        PostStatus::Rejected => &mut self.author_to_rejected_pending_posts,
+        _ => &mut self.author_to_expired_pending_posts,
+      };
+      let mut closed_post_ids = closed_posts.get(post.author).unwrap_or_default();
+      closed_post_ids.push(pending_post_id);
+      if closed_post_ids.len() > MAX_CLOSED_PENDING_POSTS_PER_AUTHOR {
+        let oldest_id = closed_post_ids.remove(0);
+        self.pending_posts.remove(oldest_id);
+        self.pending_post_tags.remove(oldest_id);
+        self.pending_shared_post_authors.remove(oldest_id);
+      }
+      closed_posts.insert(post.author, &closed_post_ids);
+    }
+
+    /// Get a published post which is not deleted
+    fn _get_post_by_id(&self, id: PostId) -> Option<Post> {
+      self._get_post(id, false)
+    }
+
+    /// Get a published post, scheduled posts are hidden until their publish time
+    fn _get_post(&self, id: PostId, include_deleted: bool) -> Option<Post> {
+      self._stored_post(id).filter(|post| (include_deleted || post.status != PostStatus::Deleted) && self._is_published(post))
+    }
+
+    /// Get a post which is not deleted, including scheduled posts
+    fn _get_existing_post(&self, id: PostId) -> Option<Post> {
+      self._stored_post(id).filter(|post| post.status != PostStatus::Deleted)
+    }
+
+    fn _is_published(&self, post: &Post) -> bool {
+      post.publish_at.map_or(true, |publish_at| self.env().block_timestamp() >= publish_at)
+    }
+
+    /// Author or space owner can archive a post, archived posts can not be pinned
+    fn _set_post_archived(&mut self, post_id: PostId, archived: bool) -> PostResult<()> {
+      let mut post = self._get_post_by_id(post_id).ok_or(PostError::PostNotExisted)?;
+
+      let caller = self.env().caller();
+      if caller != post.author && caller != self._space_owner_id() {
+        return Err(PluginError::UnAuthorized.into());
+      }
+
+      post.status = if archived { PostStatus::Archived } else { PostStatus::Published };
+      self.posts.insert(post_id, &post);
+
+      if archived {
+        self._remove_pinned_post(post_id);
+      }
+
+      Ok(())
+    }
+
+    fn _remove_pinned_post(&mut self, post_id: PostId) {
+      let mut pinned_posts = self.pinned_posts.get_or_default();
+      if pinned_posts.contains(&post_id) {
+        pinned_posts.retain(|&pinned_id| pinned_id != post_id);
+        self.pinned_posts.set(&pinned_posts);
+      }
+    }
+
+    /// Only space owner can see deleted or archived posts in listings
+    fn _include_deleted(&self, include_deleted: bool) -> bool {
+      include_deleted && self.env().caller() == self._space_owner_id()
+    }
+
+    /// Look up the polls plugin in the space's plugins, which are listed as (id, address, disabled, code_hash)
+    fn _resolve_polls_id(&self) -> PostResult<Option<AccountId>> {
+      let Some(plugin_id) = self.polls_plugin_id() else { return Ok(None) };
+
+      let plugins = build_call::<DefaultEnvironment>()
+        .call(self._space_id())
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("plugins")))
+        )
+        .returns::<Vec<(PluginId, AccountId, bool, Hash)>>()
+        .try_invoke()
+        .ok()
+        .and_then(|rs| rs.ok())
+        .ok_or(PostError::PollsUnavailable)?;
+
+      Ok(
+        plugins.into_iter()
+          .find(|(id, _, disabled, _)| *id == plugin_id && !disabled)
+          .map(|(_, address, _, _)| address)
+      )
+    }
+
+    /// Linked poll must exist in the polls plugin of the space
+    fn _ensure_valid_linked_poll(&self, linked_poll: Option<u32>) -> PostResult<()> {
+      if let Some(poll_id) = linked_poll {
+        let polls_id = self._resolve_polls_id()?.ok_or(PostError::PollNotFound)?;
+
+        let polls_count = build_call::<DefaultEnvironment>()
+          .call(polls_id)
+          .gas_limit(0)
+          .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("polls_count")))
+          )
+          .returns::<u32>()
+          .try_invoke()
+          .ok()
+          .and_then(|rs| rs.ok())
+          .ok_or(PostError::PollsUnavailable)?;
+
+        ensure!(poll_id < polls_count, PostError::PollNotFound);
+      }
+
+      Ok(())
+    }
+
+    fn _ensure_no_pending_mandatory_vote(&self, who: AccountId) -> PostResult<()> {
+      if let Some(polls_id) = self._resolve_polls_id()? {
+        let has_pending_vote = build_call::<DefaultEnvironment>()
+          .call(polls_id)
+          .gas_limit(0)
+          .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("has_pending_mandatory_vote")))
+              .push_arg(who)
+          )
+          .returns::<bool>()
+          .try_invoke()
+          .ok()
+          .and_then(|rs| rs.ok())
+          .ok_or(PostError::PollsUnavailable)?;
+
+        if has_pending_vote {
+          return Err(PostError::MandatoryVotePending);
+        }
+      }
+
+      Ok(())
+    }
+
+    fn _ensure_valid_content(&self, content: &PostContent) -> PostResult<()> {
+      match content {
+        PostContent::Raw(raw) => {
+          ensure!(raw.len() <= self.max_content_len() as usize, PostError::ContentTooLarge);
+        }
+        PostContent::IpfsCid(cid) => {
+          ensure!(validate_ipfs_cid(cid), PostError::InvalidCid);
+        }
+        PostContent::MarkdownRaw(raw) => {
+          // markdown stays enabled for spaces which have not configured the flag
+          ensure!(self._feature_flag(MARKDOWN_ENABLED_FLAG, true), PostError::FeatureDisabled);
+          ensure!(raw.len() <= self.max_content_len() as usize, PostError::ContentTooLarge);
+        }
+        PostContent::Structured { title, body } => {
+          ensure!(!title.is_empty(), PostError::Custom(String::from("Title is required")));
+          ensure!(title.len() <= MAX_TITLE_LEN, PostError::ContentTooLarge);
+          ensure!(body.len() <= self.max_content_len() as usize, PostError::ContentTooLarge);
+        }
+      }
+
+      Ok(())
+    }
+
+    fn _ensure_posting_not_frozen(&self) -> PostResult<()> {
+      ensure!(!self.is_posting_frozen(), PostError::PostingFrozen);
+
+      Ok(())
+    }
+
+    fn _ensure_post_permission(&self) -> PostResult<()> {
+      let permission = self.post_perm();
+
+      match permission {
+        PostPerm::SpaceOwner => Ok(self._ensure_space_owner()?),
+        PostPerm::ActiveMember | PostPerm::ActiveMemberWithApproval => Ok(self._ensure_active_member()?),
+      }
+    }
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use scale::Encode;
+
+    fn legacy_post() -> LegacyPost {
+      LegacyPost {
+        content: PostContent::Raw(String::from("Hello InSpace")),
+        author: AccountId::from([0x01; 32]),
+        created_at: 1_000,
+        updated_at: Some(2_000),
+        parent_id: None,
+      }
+    }
+
+    #[ink::test]
+    fn legacy_post_decodes_with_defaults() {
+      let encoded = legacy_post().encode();
+      assert!(Post::decode(&mut &encoded[..]).is_err());
+
+      let post = Post::from(LegacyPost::decode(&mut &encoded[..]).expect("Cannot decode legacy post"));
+      assert!(matches!(post.content, PostContent::Raw(content) if content == "Hello InSpace"));
+      assert_eq!(post.author, AccountId::from([0x01; 32]));
+      assert_eq!(post.created_at, 1_000);
+      assert_eq!(post.updated_at, Some(2_000));
+      assert_eq!(post.status, PostStatus::Published);
+      assert_eq!(post.updated_by, None);
+      assert_eq!(post.approved_at, None);
+      assert!(!post.locked);
+      assert!(post.comments_enabled);
+    }
+
+    #[ink::test]
+    fn legacy_post_is_readable_from_storage() {
+      let mapping_key: Key = 0x1234;
+      ink::env::set_contract_storage(&(mapping_key, 7 as PostId), &legacy_post());
+
+      let post = Posts::_load_post(mapping_key, 7).expect("Cannot read legacy post");
+      assert_eq!(post.author, AccountId::from([0x01; 32]));
+      assert_eq!(post.status, PostStatus::Published);
+      assert_eq!(post.updated_by, None);
+      assert!(Posts::_load_post(mapping_key, 8).is_none());
+    }
+
+    fn tags(values: &[&str]) -> Vec<String> {
+      values.iter().map(|&value| String::from(value)).collect()
+    }
+
+    #[ink::test]
+    fn tag_length_is_counted_in_characters() {
+      assert!(Posts::_normalize_tags(vec!["ă".repeat(MAX_TAG_LEN)]).is_ok());
+      assert_eq!(Posts::_normalize_tags(vec!["ă".repeat(MAX_TAG_LEN + 1)]), Err(PostError::InvalidTags));
+    }
+
+    #[ink::test]
+    fn unused_tags_are_pruned() {
+      let mut posts = Posts::default();
+      posts._update_post_tags(0, tags(&["rust", "ink"])).expect("Cannot tag post");
+      posts._update_post_tags(1, tags(&["rust"])).expect("Cannot tag post");
+      assert_eq!(posts.all_tags.get_or_default(), tags(&["rust", "ink"]));
+
+      posts._update_post_tags(0, Vec::new()).expect("Cannot untag post");
+      assert_eq!(posts.all_tags.get_or_default(), tags(&["rust"]));
+      assert!(!posts.tag_to_posts.contains(b"ink".to_vec()));
+      assert_eq!(posts.popular_tags(10), vec![(String::from("rust"), 1)]);
+    }
+
+    #[ink::test]
+    fn distinct_tags_are_bounded() {
+      let mut posts = Posts::default();
+      for post_id in 0..(MAX_DISTINCT_TAGS / MAX_TAGS_PER_POST) as PostId {
+        let post_tags = (0..MAX_TAGS_PER_POST).map(|index| format!("tag-{}-{}", post_id, index)).collect();
+        posts._update_post_tags(post_id, post_tags).expect("Cannot tag post");
+      }
+
+      assert_eq!(posts._update_post_tags(1_000, tags(&["one-too-many"])), Err(PostError::TooManyTags));
+      // existing tags can still be used
+      assert!(posts._update_post_tags(1_000, tags(&["tag-0-0"])).is_ok());
+    }
+
+    fn posts_with(count: u32) -> Posts {
+      let mut posts = Posts::default();
+      for _ in 0..count {
+        let post = posts._build_post(AccountId::from([0x01; 32]), PostContent::Raw(String::from("Hello InSpace")), None, None);
+        posts._insert_post(post);
+      }
+
+      posts
+    }
+
+    /// Ids of listed posts & whether there is a next page
+    fn list_post_ids(posts: &Posts, from: u32, per_page: u32, ordering: Ordering) -> (Vec<PostId>, bool) {
+      let page = posts.list_posts(from, per_page, ordering, false, false).expect("Cannot list posts");
+
+      (page.items.iter().map(|record| record.post_id).collect(), page.has_next_page)
+    }
+
+    #[ink::test]
+    fn list_posts_pages_through_posts_in_both_directions() {
+      let posts = posts_with(7);
+
+      assert_eq!(list_post_ids(&posts, 0, 3, Ordering::Ascending), (vec![0, 1, 2], true));
+      assert_eq!(list_post_ids(&posts, 3, 3, Ordering::Ascending), (vec![3, 4, 5], true));
+      assert_eq!(list_post_ids(&posts, 6, 3, Ordering::Ascending), (vec![6], false));
+      assert_eq!(list_post_ids(&posts, 9, 3, Ordering::Ascending), (vec![], false));
+
+      // `from = u32::MAX` or any `from` past the newest post starts from the newest post
+      assert_eq!(list_post_ids(&posts, u32::MAX, 3, Ordering::Descending), (vec![6, 5, 4], true));
+      assert_eq!(list_post_ids(&posts, 100, 3, Ordering::Descending), (vec![6, 5, 4], true));
+      assert_eq!(list_post_ids(&posts, 3, 3, Ordering::Descending), (vec![3, 2, 1], true));
+      assert_eq!(list_post_ids(&posts, 0, 3, Ordering::Descending), (vec![0], false));
+    }
+
+    #[ink::test]
+    fn list_posts_of_empty_space() {
+      let posts = posts_with(0);
+
+      assert_eq!(list_post_ids(&posts, 0, 10, Ordering::Ascending), (vec![], false));
+      assert_eq!(list_post_ids(&posts, u32::MAX, 10, Ordering::Descending), (vec![], false));
+    }
+
+    #[ink::test]
+    fn list_posts_in_single_page() {
+      let posts = posts_with(2);
+
+      assert_eq!(list_post_ids(&posts, 0, 10, Ordering::Ascending), (vec![0, 1], false));
+      assert_eq!(list_post_ids(&posts, u32::MAX, 10, Ordering::Descending), (vec![1, 0], false));
+    }
+
+    #[ink::test]
+    fn list_posts_across_page_boundary() {
+      let posts = posts_with(6);
+
+      assert_eq!(list_post_ids(&posts, 0, 3, Ordering::Ascending), (vec![0, 1, 2], true));
+      assert_eq!(list_post_ids(&posts, 3, 3, Ordering::Ascending), (vec![3, 4, 5], false));
+
+      assert_eq!(list_post_ids(&posts, u32::MAX, 3, Ordering::Descending), (vec![5, 4, 3], true));
+      assert_eq!(list_post_ids(&posts, 2, 3, Ordering::Descending), (vec![2, 1, 0], false));
+    }
+  }
+
+  #[cfg(all(test, feature = "e2e-tests"))]
+  mod e2e_tests {
+    use super::*;
+    use ink_e2e::build_message;
+    use mock_launcher::MockLauncherRef;
+    use motherspace::MotherSpaceRef;
+    use scale::Encode;
+    use shared::traits::plugin_launcher::PluginLauncher;
+    use shared::traits::space_profile::SpaceInfo;
+    use space::SpaceRef;
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+    type E2EClient = ink_e2e::Client<ink_e2e::PolkadotConfig, DefaultEnvironment>;
+
+    /// Deploy motherspace & a free pay to join space owned by alice
+    async fn deploy_space(client: &mut E2EClient) -> AccountId {
+      let space_code = client
+        .upload("space", &ink_e2e::alice(), None)
+        .await
+        .expect("upload space failed")
+        .code_hash;
+
+      let motherspace_id = client
+        .instantiate("motherspace", &ink_e2e::alice(), MotherSpaceRef::new(space_code, ink_e2e::account_id(ink_e2e::AccountKeyring::Alice)), 0, None)
+        .await
+        .expect("instantiate motherspace failed")
+        .account_id;
+
+      let info = SpaceInfo { name: String::from("InSpace Test"), desc: None, logo: None };
+      let deploy_new_space = build_message::<MotherSpaceRef>(motherspace_id.clone())
+        .call(|motherspace| motherspace.deploy_new_space(info, None, None, None));
+
+      let (space_id, _) = client
+        .call(&ink_e2e::alice(), deploy_new_space, 0, None)
+        .await
+        .expect("deploy space failed")
+        .return_value()
+        .expect("deploy space returned error");
+
+      space_id
+    }
+
+    async fn deploy_posts(client: &mut E2EClient, space_id: AccountId, post_perm: PostPerm) -> AccountId {
+      let constructor = PostsRef::new(space_id, ink_e2e::account_id(ink_e2e::AccountKeyring::Alice), post_perm.encode());
+
+      client
+        .instantiate("posts", &ink_e2e::alice(), constructor, 0, None)
+        .await
+        .expect("instantiate posts failed")
+        .account_id
+    }
+
+    async fn join_space(client: &mut E2EClient, space_id: AccountId, signer: &ink_e2e::Keypair, client_nonce: u32) {
+      let pay_to_join = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.pay_to_join(None, None, client_nonce));
+
+      client.call(signer, pay_to_join, 0, None).await.expect("join space failed");
+    }
+
+    async fn new_raw_post(client: &mut E2EClient, posts_id: AccountId, signer: &ink_e2e::Keypair) -> (PostCreationStatus, PostId) {
+      let new_post = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.new_post(PostContent::Raw(String::from("Hello InSpace")), None, Vec::new(), None));
+
+      client
+        .call(signer, new_post, 0, None)
+        .await
+        .expect("new post failed")
+        .return_value()
+        .expect("new post returned error")
+    }
+
+    async fn first_post(client: &mut E2EClient, posts_id: AccountId) -> PostRecordWithStats {
+      let list_posts = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.list_posts(0, 10, Ordering::Ascending, false, false));
+
+      client
+        .call_dry_run(&ink_e2e::alice(), &list_posts, 0, None)
+        .await
+        .return_value()
+        .expect("list posts returned error")
+        .items
+        .remove(0)
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn share_post_keeps_original_author(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let source_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMember).await;
+      let target_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMember).await;
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let (_, source_post_id) = new_raw_post(&mut client, source_id, &ink_e2e::bob()).await;
+
+      let share_post = build_message::<PostsRef>(target_id.clone())
+        .call(|posts| posts.share_post(source_id, source_post_id));
+      let (status, _) = client
+        .call(&ink_e2e::alice(), share_post, 0, None)
+        .await
+        .expect("share post failed")
+        .return_value()
+        .expect("share post returned error");
+      assert!(matches!(status, PostCreationStatus::Created));
+
+      let record = first_post(&mut client, target_id).await;
+      assert_eq!(record.post.author, ink_e2e::account_id(ink_e2e::AccountKeyring::Bob));
+      assert_eq!(record.post.shared_from, Some((source_id, source_post_id)));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn share_post_requires_post_permission(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let source_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+      let target_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let (_, source_post_id) = new_raw_post(&mut client, source_id, &ink_e2e::alice()).await;
+
+      // only space owner can post to the target
+      let share_post = build_message::<PostsRef>(target_id.clone())
+        .call(|posts| posts.share_post(source_id, source_post_id));
+      let result = client
+        .call_dry_run(&ink_e2e::bob(), &share_post, 0, None)
+        .await
+        .return_value();
+      assert_eq!(result, Err(PostError::PluginError(PluginError::NotSpaceOwner)));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn share_post_waits_for_approval(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let source_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+      let target_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMemberWithApproval).await;
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let (_, source_post_id) = new_raw_post(&mut client, source_id, &ink_e2e::alice()).await;
+
+      let share_post = build_message::<PostsRef>(target_id.clone())
+        .call(|posts| posts.share_post(source_id, source_post_id));
+      let (status, pending_post_id) = client
+        .call(&ink_e2e::bob(), share_post, 0, None)
+        .await
+        .expect("share post failed")
+        .return_value()
+        .expect("share post returned error");
+      assert!(matches!(status, PostCreationStatus::Pending));
+
+      let approve = build_message::<PostsRef>(target_id.clone())
+        .call(|posts| posts.submit_pending_post_approvals(vec![(pending_post_id, true)]));
+      client.call(&ink_e2e::alice(), approve, 0, None).await.expect("approve failed");
+
+      // the source author is credited, not the member sharing the post
+      let record = first_post(&mut client, target_id).await;
+      assert_eq!(record.post.author, ink_e2e::account_id(ink_e2e::AccountKeyring::Alice));
+      assert_eq!(record.post.shared_from, Some((source_id, source_post_id)));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn share_post_from_non_posts_contract_fails(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let target_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+
+      let share_post = build_message::<PostsRef>(target_id.clone())
+        .call(|posts| posts.share_post(space_id, 0));
+      let result = client
+        .call_dry_run(&ink_e2e::alice(), &share_post, 0, None)
+        .await
+        .return_value();
+      assert_eq!(result, Err(PostError::Custom(String::from("Cannot fetch post from source"))));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn tips_are_returned_with_post(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+      let (_, post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+
+      // only members can tip
+      let tip_author = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.tip_author(post_id));
+      let result = client.call_dry_run(&ink_e2e::bob(), &tip_author, 1_000, None).await.return_value();
+      assert_eq!(result, Err(PostError::PluginError(PluginError::NotActiveMember)));
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let tip_author = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.tip_author(post_id));
+      client.call(&ink_e2e::bob(), tip_author, 1_000, None).await.expect("tip author failed");
+
+      let post_by_id = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.post_by_id(post_id));
+      let record = client
+        .call_dry_run(&ink_e2e::bob(), &post_by_id, 0, None)
+        .await
+        .return_value()
+        .expect("post by id returned error")
+        .expect("post not found");
+      assert_eq!(record.tips_total, 1_000);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn listings_require_read_permission(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+      let (_, post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+
+      let update_read_perm = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.update_read_perm(ReadPerm::ActiveMember));
+      client.call(&ink_e2e::alice(), update_read_perm, 0, None).await.expect("update read perm failed");
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let not_a_member = Err(PostError::PluginError(PluginError::NotActiveMember));
+
+      let list_pinned_posts = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.list_pinned_posts(false));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &list_pinned_posts, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &list_pinned_posts, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      let list_comments = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.list_comments(post_id, 0, 10));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &list_comments, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &list_comments, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      let list_replies = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.list_replies(post_id, 0, 0, 10));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &list_replies, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &list_replies, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      let comments_by_post = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.comments_by_post(post_id));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &comments_by_post, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &comments_by_post, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      let post_revisions = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.post_revisions(post_id, 0, 10));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &post_revisions, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &post_revisions, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      let posts_by_author = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.posts_by_author(alice, 0, 10, Ordering::Ascending));
+      assert!(client.call_dry_run(&ink_e2e::bob(), &posts_by_author, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &posts_by_author, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      let list_scheduled_posts = build_message::<PostsRef>(posts_id.clone()).call(|posts| posts.list_scheduled_posts());
+      assert!(client.call_dry_run(&ink_e2e::bob(), &list_scheduled_posts, 0, None).await.return_value().is_ok());
+      assert_eq!(client.call_dry_run(&ink_e2e::charlie(), &list_scheduled_posts, 0, None).await.return_value().map(|_| ()), not_a_member);
+
+      Ok(())
+    }
+
+    /// Queue a pending post of `signer` & have alice reject it
+    async fn rejected_pending_post(client: &mut E2EClient, posts_id: AccountId, signer: &ink_e2e::Keypair) -> PostId {
+      let (status, pending_post_id) = new_raw_post(client, posts_id, signer).await;
+      assert!(matches!(status, PostCreationStatus::Pending));
+
+      let reject = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.submit_pending_post_approvals(vec![(pending_post_id, false)]));
+      client.call(&ink_e2e::alice(), reject, 0, None).await.expect("reject failed");
+
+      pending_post_id
+    }
+
+    async fn next_post_available_at(client: &mut E2EClient, posts_id: AccountId, who: AccountId) -> Option<Timestamp> {
+      let next_post_available_at = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.next_post_available_at(who));
+
+      client.call_dry_run(&ink_e2e::alice(), &next_post_available_at, 0, None).await.return_value()
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn resubmitted_post_is_consumed(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMemberWithApproval).await;
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let rejected_id = rejected_pending_post(&mut client, posts_id, &ink_e2e::bob()).await;
+
+      let resubmit = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.resubmit_pending_post(rejected_id, None));
+      let new_pending_post_id = client
+        .call(&ink_e2e::bob(), resubmit, 0, None)
+        .await
+        .expect("resubmit failed")
+        .return_value()
+        .expect("resubmit returned error");
+
+      let resubmission_of = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.resubmission_of(new_pending_post_id));
+      assert_eq!(client.call_dry_run(&ink_e2e::bob(), &resubmission_of, 0, None).await.return_value(), Some(rejected_id));
+
+      // cannot resubmit twice
+      let resubmit = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.resubmit_pending_post(rejected_id, None));
+      let result = client.call_dry_run(&ink_e2e::bob(), &resubmit, 0, None).await.return_value();
+      assert_eq!(result, Err(PostError::PostNotExisted));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn resubmit_respects_pending_post_caps(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMemberWithApproval).await;
+
+      let set_pending_post_limit = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.set_pending_post_limit(1));
+      client.call(&ink_e2e::alice(), set_pending_post_limit, 0, None).await.expect("set pending post limit failed");
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let rejected_id = rejected_pending_post(&mut client, posts_id, &ink_e2e::bob()).await;
+      new_raw_post(&mut client, posts_id, &ink_e2e::bob()).await;
+
+      let resubmit = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.resubmit_pending_post(rejected_id, None));
+      let result = client.call_dry_run(&ink_e2e::bob(), &resubmit, 0, None).await.return_value();
+      assert_eq!(result, Err(PostError::PendingPostLimitExceeded(1)));
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn resubmit_applies_post_cooldown(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMemberWithApproval).await;
+
+      // 1ms cooldown is over by the next block
+      let set_post_cooldown = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.set_post_cooldown(1));
+      client.call(&ink_e2e::alice(), set_post_cooldown, 0, None).await.expect("set post cooldown failed");
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let rejected_id = rejected_pending_post(&mut client, posts_id, &ink_e2e::bob()).await;
+      let posted_available_at = next_post_available_at(&mut client, posts_id, bob).await.expect("no cooldown after posting");
+
+      // resubmitting is recorded as the latest post
+      let resubmit = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.resubmit_pending_post(rejected_id, None));
+      let new_pending_post_id = client
+        .call(&ink_e2e::bob(), resubmit, 0, None)
+        .await
+        .expect("resubmit failed")
+        .return_value()
+        .expect("resubmit returned error");
+      let resubmitted_available_at = next_post_available_at(&mut client, posts_id, bob).await.expect("no cooldown after resubmitting");
+      assert!(resubmitted_available_at > posted_available_at);
+
+      let reject = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.submit_pending_post_approvals(vec![(new_pending_post_id, false)]));
+      client.call(&ink_e2e::alice(), reject, 0, None).await.expect("reject failed");
+
+      let set_post_cooldown = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.set_post_cooldown(3_600_000));
+      client.call(&ink_e2e::alice(), set_post_cooldown, 0, None).await.expect("set post cooldown failed");
+
+      let retry_after = next_post_available_at(&mut client, posts_id, bob).await.expect("no active cooldown");
+      let resubmit = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.resubmit_pending_post(new_pending_post_id, None));
+      let result = client.call_dry_run(&ink_e2e::bob(), &resubmit, 0, None).await.return_value();
+      assert_eq!(result, Err(PostError::CooldownActive(retry_after)));
+
+      Ok(())
+    }
+
+    async fn post_by_id(client: &mut E2EClient, posts_id: AccountId, post_id: PostId) -> PostRecord {
+      let post_by_id = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.post_by_id(post_id));
+
+      client
+        .call_dry_run(&ink_e2e::alice(), &post_by_id, 0, None)
+        .await
+        .return_value()
+        .expect("post by id returned error")
+        .expect("post not found")
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn approved_at_is_only_set_on_approval(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMemberWithApproval).await;
+
+      // space owner publishes directly
+      let (status, post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+      assert!(matches!(status, PostCreationStatus::Created));
+      assert_eq!(post_by_id(&mut client, posts_id, post_id).await.post.approved_at, None);
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let (status, pending_post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::bob()).await;
+      assert!(matches!(status, PostCreationStatus::Pending));
+
+      let approve = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.submit_pending_post_approvals(vec![(pending_post_id, true)]));
+      client.call(&ink_e2e::alice(), approve, 0, None).await.expect("approve failed");
+
+      let approved = post_by_id(&mut client, posts_id, post_id + 1).await;
+      assert_eq!(approved.post.author, ink_e2e::account_id(ink_e2e::AccountKeyring::Bob));
+      assert!(approved.post.approved_at.is_some());
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml ../../mocks/launcher/Cargo.toml")]
+    async fn set_code_hash_requires_code_known_by_launcher(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      let space_id = deploy_space(&mut client).await;
+
+      // alice acts as motherspace of the launcher, which starts with the placeholder code [0x01; 32] as version 1
+      let launcher_id = client
+        .instantiate("mock_launcher", &ink_e2e::alice(), MockLauncherRef::new(alice, 0), 0, None)
+        .await
+        .expect("instantiate mock launcher failed")
+        .account_id;
+      let constructor = PostsRef::new(space_id, launcher_id, PostPerm::SpaceOwner.encode());
+      let posts_id = client
+        .instantiate("posts", &ink_e2e::alice(), constructor, 0, None)
+        .await
+        .expect("instantiate posts failed")
+        .account_id;
+
+      let set_code_hash = |code_hash: Hash| build_message::<PostsRef>(posts_id.clone())
+        .call(move |posts| posts.set_code_hash(code_hash));
+      let not_recognized = Err(PluginError::Custom(String::from("Code hash not recognized by launcher")));
+
+      let result = client.call_dry_run(&ink_e2e::alice(), &set_code_hash(Hash::from([0x09; 32])), 0, None).await.return_value();
+      assert_eq!(result, not_recognized);
+
+      // known codes pass the check, setting them fails only because no such code is uploaded
+      let result = client.call_dry_run(&ink_e2e::alice(), &set_code_hash(Hash::from([0x01; 32])), 0, None).await.return_value();
+      assert_eq!(result, Err(PluginError::SetCodeHashFailed));
+
+      let upgrade_plugin_code = build_message::<MockLauncherRef>(launcher_id.clone())
+        .call(|launcher| launcher.upgrade_plugin_code(Hash::from([0x02; 32])));
+      client.call(&ink_e2e::alice(), upgrade_plugin_code, 0, None).await.expect("upgrade plugin code failed");
+
+      // older versions are still known
+      for code_hash in [Hash::from([0x01; 32]), Hash::from([0x02; 32])] {
+        let result = client.call_dry_run(&ink_e2e::alice(), &set_code_hash(code_hash), 0, None).await.return_value();
+        assert_eq!(result, Err(PluginError::SetCodeHashFailed));
+      }
+      let result = client.call_dry_run(&ink_e2e::alice(), &set_code_hash(Hash::from([0x09; 32])), 0, None).await.return_value();
+      assert_eq!(result, not_recognized);
+
+      // the check can be skipped in emergencies
+      let set_allow_arbitrary_code = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.set_allow_arbitrary_code(true));
+      client.call(&ink_e2e::alice(), set_allow_arbitrary_code, 0, None).await.expect("set allow arbitrary code failed");
+      let result = client.call_dry_run(&ink_e2e::alice(), &set_code_hash(Hash::from([0x09; 32])), 0, None).await.return_value();
+      assert_eq!(result, Err(PluginError::SetCodeHashFailed));
+
+      Ok(())
+    }
+
+    async fn pending_post_ids_by_author(client: &mut E2EClient, posts_id: AccountId, signer: &ink_e2e::Keypair) -> Vec<PostId> {
+      let pending_posts_by_author = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.pending_posts_by_author(None));
+
+      client
+        .call_dry_run(signer, &pending_posts_by_author, 0, None)
+        .await
+        .return_value()
+        .expect("pending posts by author returned error")
+        .iter()
+        .map(|record| record.post_id)
+        .collect()
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn author_keeps_all_pending_posts(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::ActiveMemberWithApproval).await;
+
+      join_space(&mut client, space_id, &ink_e2e::bob(), 1).await;
+      let mut pending_post_ids = Vec::new();
+      for _ in 0..4 {
+        let (status, pending_post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::bob()).await;
+        assert!(matches!(status, PostCreationStatus::Pending));
+        pending_post_ids.push(pending_post_id);
+      }
+      assert_eq!(pending_post_ids_by_author(&mut client, posts_id, &ink_e2e::bob()).await, pending_post_ids);
+
+      let cancel_pending_post = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.cancel_pending_post(pending_post_ids[0]));
+      client.call(&ink_e2e::bob(), cancel_pending_post, 0, None).await.expect("cancel pending post failed");
+      assert_eq!(pending_post_ids_by_author(&mut client, posts_id, &ink_e2e::bob()).await, pending_post_ids[1..].to_vec());
+
+      let approvals = vec![(pending_post_ids[1], true), (pending_post_ids[2], false), (pending_post_ids[3], true)];
+      let submit_approvals = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.submit_pending_post_approvals(approvals));
+      let result = client
+        .call(&ink_e2e::alice(), submit_approvals, 0, None)
+        .await
+        .expect("submit approvals failed")
+        .return_value()
+        .expect("submit approvals returned error");
+      assert_eq!((result.approved, result.rejected, result.not_found), (2, 1, 0));
+      assert!(pending_post_ids_by_author(&mut client, posts_id, &ink_e2e::bob()).await.is_empty());
+
+      let rejected_pending_posts_by_author = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.rejected_pending_posts_by_author(None));
+      let rejected = client
+        .call_dry_run(&ink_e2e::bob(), &rejected_pending_posts_by_author, 0, None)
+        .await
+        .return_value()
+        .expect("rejected pending posts by author returned error");
+      assert_eq!(rejected.iter().map(|record| record.post_id).collect::<Vec<_>>(), vec![pending_post_ids[2]]);
+
+      Ok(())
+    }
+
+    /// Active, ever created & deleted posts counts
+    async fn posts_counts(client: &mut E2EClient, posts_id: AccountId) -> (u32, u32, u32) {
+      let posts_count = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.posts_count());
+      let posts_ever_created = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.posts_ever_created());
+      let deleted_posts_count = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.deleted_posts_count());
+
+      (
+        client.call_dry_run(&ink_e2e::alice(), &posts_count, 0, None).await.return_value(),
+        client.call_dry_run(&ink_e2e::alice(), &posts_ever_created, 0, None).await.return_value(),
+        client.call_dry_run(&ink_e2e::alice(), &deleted_posts_count, 0, None).await.return_value(),
+      )
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn posts_counts_follow_create_delete_create(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+
+      let (_, first_post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+      new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+      assert_eq!(posts_counts(&mut client, posts_id).await, (2, 2, 0));
+
+      let delete_post = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.delete_post(first_post_id));
+      client.call(&ink_e2e::alice(), delete_post, 0, None).await.expect("delete post failed");
+      assert_eq!(posts_counts(&mut client, posts_id).await, (1, 2, 1));
+
+      new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+      assert_eq!(posts_counts(&mut client, posts_id).await, (2, 3, 1));
+
+      let list_posts = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.list_posts(0, 10, Ordering::Ascending, false, false));
+      let page = client
+        .call_dry_run(&ink_e2e::alice(), &list_posts, 0, None)
+        .await
+        .return_value()
+        .expect("list posts returned error");
+      assert_eq!(page.total, 2);
+      assert_eq!(page.items.len(), 2);
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn markdown_flag_changes_take_effect_immediately(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+      const PLUGIN_ID: PluginId = *b"POST";
+
+      // alice acts as motherspace of the space so she can attach the posts plugin herself
+      let info = SpaceInfo { name: String::from("InSpace Test"), desc: None, logo: None };
+      let space_id = client
+        .instantiate("space", &ink_e2e::alice(), SpaceRef::new(alice, alice, info, None), 0, None)
+        .await
+        .expect("instantiate space failed")
+        .account_id;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+
+      let attach_plugins = build_message::<SpaceRef>(space_id.clone())
+        .call(|space| space.attach_plugins(vec![(PLUGIN_ID, posts_id)]));
+      client.call(&ink_e2e::alice(), attach_plugins, 0, None).await.expect("attach plugins failed");
+
+      let new_markdown_post = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.new_post(PostContent::MarkdownRaw(String::from("# Hello InSpace")), None, Vec::new(), None));
+      let set_markdown_enabled = |enabled: bool| build_message::<SpaceRef>(space_id.clone())
+        .call(move |space| space.set_plugin_feature_flag(PLUGIN_ID, String::from("markdown_enabled"), enabled));
+
+      // markdown is accepted until the owner turns it off
+      let result = client.call_dry_run(&ink_e2e::alice(), &new_markdown_post, 0, None).await.return_value();
+      assert!(result.is_ok());
+
+      client.call(&ink_e2e::alice(), set_markdown_enabled(false), 0, None).await.expect("set feature flag failed");
+      let result = client.call_dry_run(&ink_e2e::alice(), &new_markdown_post, 0, None).await.return_value();
+      assert!(matches!(result, Err(PostError::FeatureDisabled)));
+
+      client.call(&ink_e2e::alice(), set_markdown_enabled(true), 0, None).await.expect("set feature flag failed");
+      let result = client.call_dry_run(&ink_e2e::alice(), &new_markdown_post, 0, None).await.return_value();
+      assert!(result.is_ok());
+
+      Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "../../motherspace/Cargo.toml ../../space/Cargo.toml")]
+    async fn comments_by_post_skips_deleted_comments(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+      let space_id = deploy_space(&mut client).await;
+      let posts_id = deploy_posts(&mut client, space_id, PostPerm::SpaceOwner).await;
+      let (_, post_id) = new_raw_post(&mut client, posts_id, &ink_e2e::alice()).await;
+
+      let mut comment_ids = Vec::new();
+      for content in ["First comment", "Second comment"] {
+        let new_comment = build_message::<PostsRef>(posts_id.clone())
+          .call(|posts| posts.new_comment(post_id, PostContent::Raw(String::from(content)), None));
+        let comment_id = client
+          .call(&ink_e2e::alice(), new_comment, 0, None)
+          .await
+          .expect("new comment failed")
+          .return_value()
+          .expect("new comment returned error");
+        comment_ids.push(comment_id);
+      }
+
+      let delete_comment = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.delete_comment(comment_ids[0]));
+      client.call(&ink_e2e::alice(), delete_comment, 0, None).await.expect("delete comment failed");
+
+      let comments_by_post = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.comments_by_post(post_id));
+      let comments = client
+        .call_dry_run(&ink_e2e::alice(), &comments_by_post, 0, None)
+        .await
+        .return_value()
+        .expect("comments by post returned error");
+      assert_eq!(comments.iter().map(|record| record.post_id).collect::<Vec<_>>(), vec![comment_ids[1]]);
+
+      // posts without comments have none
+      let comments_by_post = build_message::<PostsRef>(posts_id.clone())
+        .call(|posts| posts.comments_by_post(post_id + 1));
+      let comments = client
+        .call_dry_run(&ink_e2e::alice(), &comments_by_post, 0, None)
+        .await
+        .return_value()
+        .expect("comments by post returned error");
+      assert!(comments.is_empty());
+
+      Ok(())
     }
   }
 }
@@ -7,17 +7,42 @@ pub use space::{SpaceRef};
 mod space {
   use ink::env::call::{build_call, ExecutionInput, Selector};
   use ink::env::{DefaultEnvironment};
+  use ink::primitives::Key;
   use ink::storage::{Mapping, Lazy};
+  use ink::storage::traits::StorageKey;
   use ink::prelude::string::String;
   use ink::prelude::vec::Vec;
   use openbrush::{modifiers, traits::Storage};
+  use openbrush::contracts::psp22::PSP22Error;
   use shared::ensure;
   use shared::traits::codehash::*;
+  use shared::traits::migratable::*;
+  use shared::traits::motherspace::MotherSpaceResult;
   use shared::traits::space_profile::*;
 
   type SpaceResult<T> = core::result::Result<T, SpaceError>;
 
+  /// Bump this whenever a migration step is added to `migrate`
+  const LATEST_STORAGE_VERSION: StorageVersion = 7;
+
+  /// Hard limit of pending membership requests, spaces can set a lower limit in config
   const MAX_PENDING_REQUESTS: u64 = 500;
+  const MAX_WELCOME_MESSAGE_LEN: usize = 500;
+  const MAX_JOIN_QUESTIONS: usize = 5;
+  const MAX_JOIN_QUESTION_LEN: usize = 200;
+  const MAX_JOIN_ANSWER_LEN: usize = 500;
+  const MAX_RECENT_REFERRALS: usize = 20;
+  const MAX_RECENT_JOINS: usize = 100;
+  const MAX_FEATURE_FLAG_LEN: usize = 30;
+  const MAX_MEMBER_BIO_LEN: usize = 200;
+  const MAX_MEMBER_WEBSITE_LEN: usize = 200;
+  const MAX_MEMBER_SKILLS: usize = 10;
+  const MAX_SPACE_SKILLS: usize = 200;
+  const MAX_WITHDRAWAL_MEMO_LEN: usize = 200;
+  const MAX_PLUGINS_PER_SPACE: usize = 20;
+  const MAX_EXPIRING_SOON_SCAN: u32 = 500;
+  /// Members visited per `migrate` call by migration steps walking through all members
+  const MIGRATION_BATCH_SIZE: u32 = 100;
 
   #[derive(Clone, Debug, Default, scale::Decode, scale::Encode)]
   #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
@@ -26,6 +51,40 @@ mod space {
     /// None -> non expiring, Some(>0) -> expiring, Some(0) -> member already left
     next_renewal_at: Option<Timestamp>,
     joined_at: Timestamp,
+    bio: Option<String>,
+    avatar: Option<ImageSource>,
+    website: Option<String>,
+    skills: Option<Vec<String>>,
+  }
+
+  /// Member layout before profile fields were appended, only used to read members stored before the upgrade
+  #[derive(scale::Encode, scale::Decode)]
+  struct LegacyMemberInfo {
+    name: Option<String>,
+    next_renewal_at: Option<Timestamp>,
+    joined_at: Timestamp,
+  }
+
+  impl From<LegacyMemberInfo> for MemberInfo {
+    fn from(legacy: LegacyMemberInfo) -> Self {
+      MemberInfo {
+        name: legacy.name,
+        next_renewal_at: legacy.next_renewal_at,
+        joined_at: legacy.joined_at,
+        ..Default::default()
+      }
+    }
+  }
+
+  /// Member profile without membership timestamps
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct MemberInfoPublic {
+    name: Option<String>,
+    bio: Option<String>,
+    avatar: Option<ImageSource>,
+    website: Option<String>,
+    skills: Option<Vec<String>>,
   }
 
   type RequestId = u32;
@@ -73,6 +132,19 @@ mod space {
     rejected: u32,
     // number of not found requests
     not_found: u32,
+    // welcome message, returned if there are new members approved
+    welcome_message: Option<String>,
+  }
+
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+  pub struct BatchCancelResult {
+    // number of cancelled & refunded requests
+    cancelled: u32,
+    // requests kept pending because refunding failed
+    refund_failed: Vec<RequestId>,
+    // number of not found requests
+    not_found: u32,
   }
 
   #[derive(Clone, Debug, PartialEq, scale::Decode, scale::Encode)]
@@ -93,6 +165,16 @@ mod space {
     code_hash: Hash
   }
 
+  #[derive(Clone, Debug, scale::Decode, scale::Encode)]
+  #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+  pub struct TreasuryWithdrawal {
+    amount: Balance,
+    to: AccountId,
+    memo: String,
+    at: Timestamp,
+    by: AccountId,
+  }
+
   #[ink(storage)]
   #[derive(Default, Storage)]
   pub struct Space {
@@ -101,30 +183,124 @@ mod space {
 
     // Membership
     members_nonce: Lazy<u32>,
+    // number of members who have not left the space
+    non_left_members_count: Lazy<u32>,
     members: Mapping<AccountId, MemberInfo>,
     index_to_member: Mapping<u32, AccountId>,
+    member_indexes: Mapping<AccountId, u32>,
+    last_left_at: Mapping<AccountId, Timestamp>,
+    // latest joins, oldest first
+    recent_joins: Lazy<Vec<(AccountId, Timestamp)>>,
 
     // Membership requests
     requests: Mapping<RequestId, MembershipRequest>,
     registrant_to_request: Mapping<AccountId, RequestId>,
     pending_requests: Lazy<Vec<RequestId>>,
     requests_nonce: Lazy<u32>,
+    join_questionnaire: Lazy<Option<Vec<String>>>,
+    request_answers: Mapping<RequestId, Vec<String>>,
+    request_referrers: Mapping<RequestId, AccountId>,
+    // account allowed to pay to join on behalf of a registrant
+    authorized_payers: Mapping<AccountId, AccountId>,
+    // (token contract, amount) paid by requests with token pricing
+    request_token_payments: Mapping<RequestId, (AccountId, Balance)>,
+    // token payments of pending requests by token contract, held until the requests are approved or refunded
+    escrowed_token_payments: Mapping<AccountId, Balance>,
+    // native payments of pending requests, held until the requests are approved or refunded
+    escrowed_request_payments: Lazy<Balance>,
+    // client supplied nonces to prevent registering twice from resubmitted transactions
+    used_nonces: Mapping<(AccountId, u32), bool>,
+    next_client_nonce: Mapping<AccountId, u32>,
+
+    // Referrals
+    referred_by: Mapping<AccountId, AccountId>,
+    referral_count: Mapping<AccountId, u32>,
+    recent_referrals: Mapping<AccountId, Vec<AccountId>>,
+    claimed_referral_count: Mapping<AccountId, u32>,
+    referral_bonus_per_member: Lazy<Balance>,
 
     // plugins
     plugins: Mapping<PluginId, AccountId>,
     disabled_plugin_ids: Lazy<Vec<PluginId>>,
     plugin_ids: Lazy<Vec<PluginId>>,
+    plugin_address_to_id: Mapping<AccountId, PluginId>,
+    plugin_feature_flags: Mapping<(PluginId, String), bool>,
+
+    welcome_message: Lazy<Option<String>>,
+
+    // last known global pause state of motherspace
+    pause_cache: Lazy<bool>,
+
+    // Following
+    following: Mapping<(AccountId, AccountId), bool>,
+    following_ids: Mapping<AccountId, Vec<AccountId>>,
+    follower_count: Mapping<AccountId, u32>,
+    following_count: Mapping<AccountId, u32>,
+
+    // Skills
+    skill_to_members: Mapping<String, Vec<AccountId>>,
+    all_skills: Lazy<Vec<String>>,
+
+    // Treasury
+    treasury_withdrawals: Mapping<u32, TreasuryWithdrawal>,
+    withdrawal_count: Lazy<u32>,
 
     #[storage_field]
     ownable: ownable::Data,
     motherspace_id: Lazy<AccountId>,
+
+    #[storage_field]
+    migration: migratable::Data,
+    // next member index of a migration step running in batches
+    migration_cursor: Lazy<u32>,
+  }
+
+  /// Let motherspace know when the space ownership is transferred or renounced.
+  /// The ownership change is reverted if motherspace cannot record it, so both stay in sync
+  #[overrider(ownable::Internal)]
+  fn _emit_ownership_transferred_event(&self, previous: Option<AccountId>, new: Option<AccountId>) {
+    // the initial owner is recorded by motherspace on deployment
+    if previous.is_none() {
+      return;
+    }
+
+    let result = build_call::<DefaultEnvironment>()
+      .call(self.motherspace_id())
+      .gas_limit(0)
+      .exec_input(
+        ExecutionInput::new(Selector::new(ink::selector_bytes!("notify_owner_change")))
+          .push_arg(new)
+      )
+      .returns::<MotherSpaceResult<()>>()
+      .try_invoke();
+
+    assert!(matches!(result, Ok(Ok(Ok(())))), "Cannot notify motherspace of the ownership change");
   }
 
   impl CodeHash for Space {}
-  impl SpaceProfile for Space {}
+  impl Migratable for Space {}
+  impl SpaceProfile for Space {
+    fn _ensure_not_paused(&self) -> SpaceResult<()> {
+      self.ensure_not_paused()
+    }
+
+    /// Keep the space info cache of motherspace up to date
+    fn _after_info_updated(&mut self, info: &SpaceInfo) {
+      let _ = build_call::<DefaultEnvironment>()
+        .call(self.motherspace_id())
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("notify_info_update")))
+            .push_arg(&info.name)
+            .push_arg(&info.logo)
+        )
+        .returns::<MotherSpaceResult<()>>()
+        .try_invoke();
+    }
+  }
 
   impl Space {
-    #[ink(constructor)]
+    #[ink(constructor, payable)]
     pub fn new(motherspace_id: AccountId,
                owner_id: AccountId,
                space_info: SpaceInfo,
@@ -137,23 +313,60 @@ mod space {
       ownable::Internal::_init_with_owner(&mut instance, owner_id);
 
       instance.motherspace_id.set(&motherspace_id);
+      instance._set_storage_version(LATEST_STORAGE_VERSION);
       instance.do_grant_membership(owner_id, None, false)?;
 
       Ok(instance)
     }
 
+    /// Run the next migration step after a code upgrade, returns the storage version after the call.
+    /// Steps walking through all members run in batches, the version stays the same until the last batch is done
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn migrate(&mut self) -> SpaceResult<StorageVersion> {
+      let current_version = self._storage_version();
+      ensure!(current_version < LATEST_STORAGE_VERSION, SpaceError::Custom(String::from("Storage is already up to date!")));
+
+      match current_version {
+        // Subscription duration is stored in seconds instead of days
+        0 => space_profile::SpaceProfile::_migrate_subscription_duration_to_secs(self),
+        // Rejoin cooldown is added to space config
+        1 => space_profile::SpaceProfile::_migrate_add_rejoin_cooldown(self),
+        // Third party payment setting is added to space config
+        2 => space_profile::SpaceProfile::_migrate_add_third_party_payment(self),
+        // Count members who have not left
+        3 => if !self.migrate_non_left_members_count() {
+          return Ok(current_version);
+        },
+        // Pending requests capacity override is added to space config
+        4 => space_profile::SpaceProfile::_migrate_add_max_pending_requests(self),
+        // Payments of pending requests are held out of the treasury
+        5 => self.migrate_escrowed_request_payments(),
+        // Index of each member is looked up by account
+        6 => if !self.migrate_member_indexes() {
+          return Ok(current_version);
+        },
+        _ => return Err(SpaceError::Custom(String::from("No migration found"))),
+      }
+
+      let next_version = current_version.saturating_add(1);
+      self._set_storage_version(next_version);
+
+      Ok(next_version)
+    }
+
     /// Attach plugins to space, motherspace call this when install plugins for spaces
     #[ink(message)]
     pub fn attach_plugins(&mut self, plugins: Vec<(PluginId, AccountId)>) -> SpaceResult<()> {
+      self.ensure_not_paused()?;
       ensure!(self.motherspace_id() == Self::env().caller(), SpaceError::Custom(String::from("Only MotherSpace can attach plugins!")));
 
-      if plugins.iter().any(|&p| self.plugins.contains(p.0)) {
-        return Err(SpaceError::Custom(String::from("Cannot attach a plugin more than one.")));
-      }
+      self.ensure_attachable_plugins(&plugins)?;
 
       let mut plugin_ids = self.plugin_ids.get_or_default();
       for (id, address) in plugins {
         self.plugins.insert(id, &address);
+        self.plugin_address_to_id.insert(address, &id);
         plugin_ids.push(id);
       }
 
@@ -162,6 +375,69 @@ mod space {
       Ok(())
     }
 
+    fn ensure_attachable_plugins(&self, plugins: &[(PluginId, AccountId)]) -> SpaceResult<()> {
+      ensure!(
+        self.plugin_ids.get_or_default().len().saturating_add(plugins.len()) <= MAX_PLUGINS_PER_SPACE,
+        SpaceError::Custom(String::from("Exceeding maximum of plugins per space"))
+      );
+
+      ensure!(plugins.iter().all(|p| p.0 != [0u8; 4]), SpaceError::Custom(String::from("Invalid plugin id")));
+
+      for (index, plugin) in plugins.iter().enumerate() {
+        ensure!(
+          !plugins[(index + 1)..].iter().any(|other| other.0 == plugin.0),
+          SpaceError::Custom(String::from("Duplicate plugin IDs in input"))
+        );
+      }
+
+      if plugins.iter().any(|&p| self.plugins.contains(p.0)) {
+        return Err(SpaceError::PluginAlreadyInstalled);
+      }
+
+      Ok(())
+    }
+
+    /// Ids of attached plugins
+    #[ink(message)]
+    pub fn plugin_ids(&self) -> Vec<PluginId> {
+      self.plugin_ids.get_or_default()
+    }
+
+    /// Check if a plugin is attached, `plugins` is written along with `plugin_ids` so no need to load the whole list
+    #[ink(message)]
+    pub fn is_plugin_installed(&self, plugin_id: PluginId) -> bool {
+      self.plugins.contains(plugin_id)
+    }
+
+    /// Plugin id of an attached plugin address
+    #[ink(message)]
+    pub fn plugin_id_of(&self, address: AccountId) -> Option<PluginId> {
+      self.plugin_address_to_id.get(address)
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn set_plugin_feature_flag(&mut self, plugin_id: PluginId, flag: String, enabled: bool) -> SpaceResult<()> {
+      ensure!(self.is_plugin_installed(plugin_id), SpaceError::PluginNotFound);
+      ensure!(flag.len() <= MAX_FEATURE_FLAG_LEN, SpaceError::Custom(String::from("Feature flag is at max 30 chars")));
+
+      self.plugin_feature_flags.insert((plugin_id, flag), &enabled);
+
+      Ok(())
+    }
+
+    /// Feature flag of a plugin, disabled by default
+    #[ink(message)]
+    pub fn get_plugin_feature_flag(&self, plugin_id: PluginId, flag: String) -> bool {
+      self.plugin_feature_flag(plugin_id, flag).unwrap_or_default()
+    }
+
+    /// Feature flag of a plugin, None if the owner has never set it so plugins can pick their own default
+    #[ink(message)]
+    pub fn plugin_feature_flag(&self, plugin_id: PluginId, flag: String) -> Option<bool> {
+      self.plugin_feature_flags.get((plugin_id, flag))
+    }
+
     #[ink(message)]
     pub fn plugins(&self) -> Vec<PluginInfo> {
       self.plugin_ids.get_or_default()
@@ -178,7 +454,7 @@ mod space {
     #[ink(message)]
     #[modifiers(only_owner)]
     pub fn enable_plugin(&mut self, plugin_id: PluginId) -> SpaceResult<()> {
-      ensure!(self.plugin_ids.get_or_default().contains(&plugin_id), SpaceError::PluginNotFound);
+      ensure!(self.is_plugin_installed(plugin_id), SpaceError::PluginNotFound);
 
       let mut disabled_ids = self.disabled_plugin_ids.get_or_default();
       disabled_ids.retain(|&x| x != plugin_id);
@@ -190,8 +466,7 @@ mod space {
     #[ink(message)]
     #[modifiers(only_owner)]
     pub fn disable_plugin(&mut self, plugin_id: PluginId) -> SpaceResult<()> {
-      let plugin_ids = self.plugin_ids.get_or_default();
-      ensure!(plugin_ids.contains(&plugin_id), SpaceError::PluginNotFound);
+      ensure!(self.is_plugin_installed(plugin_id), SpaceError::PluginNotFound);
 
       let mut disabled_ids = self.disabled_plugin_ids.get_or_default();
       if !disabled_ids.contains(&plugin_id) {
@@ -208,8 +483,6 @@ mod space {
     }
 
     fn _plugin_code_hash(&self, plugin_id: PluginId) -> SpaceResult<Hash> {
-      let plugin_ids = self.plugin_ids.get_or_default();
-      ensure!(plugin_ids.contains(&plugin_id), SpaceError::PluginNotFound);
       let plugin_address = self.plugins.get(plugin_id).ok_or(SpaceError::PluginNotFound)?;
 
       let code_hash = build_call::<DefaultEnvironment>()
@@ -225,15 +498,35 @@ mod space {
     }
 
     /// Membership methods
+    /// Number of members who have not left the space
     #[ink(message)]
     pub fn members_count(&self) -> u32 {
+      self.non_left_members_count.get_or_default()
+    }
+
+    /// Number of accounts ever joined the space, including members who left
+    #[ink(message)]
+    pub fn ever_joined_count(&self) -> u32 {
       self.members_nonce.get_or_default()
     }
 
+    /// Latest joins, newest first, at max 100 entries
     #[ink(message)]
-    pub fn list_members(&self, from: u32, per_page: u32) -> MembersPage {
-      let last_position = from.saturating_add(per_page);
+    pub fn recently_joined_members(&self, count: u32) -> Vec<(AccountId, Timestamp)> {
+      let count = (count as usize).min(MAX_RECENT_JOINS);
+
+      self.recent_joins.get_or_default().into_iter().rev().take(count).collect()
+    }
+
+    #[ink(message)]
+    pub fn newest_member(&self) -> Option<(AccountId, Timestamp)> {
+      self.recent_joins.get_or_default().last().copied()
+    }
+
+    #[ink(message)]
+    pub fn list_members(&self, from: u32, per_page: u32, include_left: bool) -> MembersPage {
       let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
       let current_member_nonce = self.members_nonce.get_or_default();
 
       let mut member_records = Vec::new();
@@ -241,31 +534,85 @@ mod space {
         let bounded_index = index as u32;
 
         if let Some(account_id) = self.index_to_member.get(bounded_index) {
-          if let Some(info) = self.members.get(account_id) {
-            member_records.push(MemberRecord { index: bounded_index, account_id, info })
+          if let Some(info) = self.stored_member(account_id) {
+            if include_left || !Self::has_left(&info) {
+              member_records.push(MemberRecord { index: bounded_index, account_id, info })
+            }
           }
         }
       }
 
       MembersPage {
+        items: member_records,
+        from,
+        per_page,
+        has_next_page: last_position < current_member_nonce,
+        total: if include_left { current_member_nonce } else { self.members_count() },
+      }
+    }
+
+    /// Active members whose membership expires within `within_secs` from now
+    #[ink(message)]
+    pub fn expiring_soon_members(&self, within_secs: u64, from: u32, per_page: u32) -> SpaceResult<MembersPage> {
+      self.ensure_owner()?;
+
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let current_member_nonce = self.members_nonce.get_or_default();
+
+      let mut member_records = Vec::new();
+      for index in (from as usize)..(last_position.min(current_member_nonce) as usize) {
+        let bounded_index = index as u32;
+
+        if let Some(account_id) = self.index_to_member.get(bounded_index) {
+          if let Some(info) = self.stored_member(account_id) {
+            if self.is_expiring_soon(&info, within_secs) {
+              member_records.push(MemberRecord { index: bounded_index, account_id, info })
+            }
+          }
+        }
+      }
+
+      Ok(MembersPage {
         items: member_records,
         from,
         per_page,
         has_next_page: last_position < current_member_nonce,
         total: current_member_nonce,
+      })
+    }
+
+    /// Count members expiring within `within_secs` among members at index `from` to `from + per_page`,
+    /// at max 500 members are checked per call
+    #[ink(message)]
+    pub fn expiring_soon_count(&self, within_secs: u64, from: u32, per_page: u32) -> u32 {
+      let per_page = per_page.min(MAX_EXPIRING_SOON_SCAN);
+      let last_position = from.saturating_add(per_page).min(self.members_nonce.get_or_default());
+
+      let mut count: u32 = 0;
+      for index in from..last_position {
+        if let Some(info) = self.index_to_member.get(index).and_then(|account_id| self.stored_member(account_id)) {
+          if self.is_expiring_soon(&info, within_secs) {
+            count = count.saturating_add(1);
+          }
+        }
       }
+
+      count
     }
 
     #[ink(message)]
     #[modifiers(only_owner)]
-    pub fn grant_membership(&mut self, who: AccountId, ttl: Option<u64>) -> SpaceResult<()> {
+    pub fn grant_membership(&mut self, who: AccountId, ttl: Option<u64>) -> SpaceResult<Option<String>> {
       // TODO add role based access, so admin can also grant memberships
       // TODO grant multiple membership on one go
+      self.ensure_not_paused()?;
 
       self.do_grant_membership(who, ttl, true)
     }
 
-    fn do_grant_membership(&mut self, who: AccountId, ttl: Option<u64>, register_space_member: bool) -> SpaceResult<()> {
+    /// Grant membership, returns the welcome message if `who` is a new member
+    fn do_grant_membership(&mut self, who: AccountId, ttl: Option<u64>, register_space_member: bool) -> SpaceResult<Option<String>> {
       let member_status = self.member_status(who);
       ensure!(member_status != MemberStatus::Active, SpaceError::MemberExisted(who));
 
@@ -274,7 +621,14 @@ mod space {
         current_timestamp.checked_add(val).expect("Cannot extend renewal date")
       );
 
-      if member_status == MemberStatus::None {
+      if member_status == MemberStatus::Left {
+        if let Some(retry_after) = self.rejoin_available_at(who) {
+          ensure!(current_timestamp >= retry_after, SpaceError::CooldownActive { retry_after });
+        }
+      }
+
+      let is_new_member = member_status == MemberStatus::None;
+      if is_new_member {
         let new_member = MemberInfo {
           next_renewal_at,
           joined_at: current_timestamp,
@@ -289,14 +643,27 @@ mod space {
 
         self.members.insert(who, &new_member);
         self.index_to_member.insert(current_members_nonce, &who);
+        self.member_indexes.insert(who, &current_members_nonce);
         self.members_nonce.set(&next_members_nonce);
+        self.increase_non_left_members_count();
       } else {
-        let mut member_info = self.members.get(who).unwrap();
+        if member_status == MemberStatus::Left {
+          self.increase_non_left_members_count();
+        }
+
+        let mut member_info = self.stored_member(who).unwrap();
         member_info.next_renewal_at = next_renewal_at;
 
         self.members.insert(who, &member_info);
       }
 
+      let mut recent_joins = self.recent_joins.get_or_default();
+      recent_joins.push((who, current_timestamp));
+      if recent_joins.len() > MAX_RECENT_JOINS {
+        recent_joins.remove(0);
+      }
+      self.recent_joins.set(&recent_joins);
+
       // Register space member in mother space
       if register_space_member {
         let _ = build_call::<DefaultEnvironment>()
@@ -306,40 +673,129 @@ mod space {
             ExecutionInput::new(Selector::new(ink::selector_bytes!("add_space_member")))
               .push_arg(who)
           )
-          .returns::<SpaceResult<()>>()
+          .returns::<MotherSpaceResult<()>>()
           .invoke();
       }
 
+      if is_new_member {
+        Ok(self.welcome_message())
+      } else {
+        Ok(None)
+      }
+    }
+
+    /// Welcome message for new members
+    #[ink(message)]
+    pub fn welcome_message(&self) -> Option<String> {
+      self.welcome_message.get_or_default()
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn set_welcome_message(&mut self, msg: Option<String>) -> SpaceResult<()> {
+      if let Some(content) = &msg {
+        ensure!(content.len() <= MAX_WELCOME_MESSAGE_LEN, SpaceError::Custom(String::from("Welcome message is at max 500 chars")));
+      }
+
+      self.welcome_message.set(&msg);
+
       Ok(())
     }
 
     /// pay to join
     #[ink(message, payable)]
-    pub fn pay_to_join(&mut self, who: Option<AccountId>) -> SpaceResult<()> {
+    pub fn pay_to_join(&mut self, who: Option<AccountId>, referrer: Option<AccountId>, client_nonce: u32) -> SpaceResult<Option<String>> {
+      self.ensure_not_paused()?;
+      self.use_client_nonce(client_nonce)?;
+
       let config = self.config();
       ensure!(config.registration == RegistrationType::PayToJoin, SpaceError::Custom(String::from("Space doesn't support pay to join!")));
 
-      let registrant = who.unwrap_or(self.env().caller());
+      let caller = self.env().caller();
+      let registrant = who.unwrap_or(caller);
       ensure!(!self.is_member(Some(registrant)), SpaceError::MemberExisted(registrant));
 
+      if registrant != caller && !config.allow_third_party_payment {
+        ensure!(self.authorized_payers.get(registrant) == Some(caller), SpaceError::UnAuthorized);
+      }
+
       let paid_balance: Balance = self.env().transferred_value();
 
       let valid_payment = match config.pricing {
         Pricing::Free => true,
         Pricing::OneTimePaid { price } => paid_balance >= price,
-        Pricing::Subscription { price, .. } => paid_balance >= price
+        Pricing::Subscription { price, .. } => paid_balance >= price,
+        Pricing::SubscriptionWithToken { token_contract, price, .. } => {
+          self.collect_token_payment(token_contract, caller, price)?;
+          true
+        }
       };
 
       ensure!(valid_payment, SpaceError::InsufficientPayment);
+      self.ensure_valid_referrer(registrant, referrer)?;
 
-      self.do_grant_membership(registrant, config.ttl(), true)
+      let welcome_message = self.do_grant_membership(registrant, config.ttl(), true)?;
+      self.record_referral(registrant, referrer);
+
+      Ok(welcome_message)
+    }
+
+    /// Allow `payer` to pay to join on behalf of the caller
+    #[ink(message)]
+    pub fn authorize_payer(&mut self, payer: AccountId) -> SpaceResult<()> {
+      self.authorized_payers.insert(self.env().caller(), &payer);
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn revoke_payer_authorization(&mut self) -> SpaceResult<()> {
+      self.authorized_payers.remove(self.env().caller());
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn authorized_payer(&self, who: AccountId) -> Option<AccountId> {
+      self.authorized_payers.get(who)
+    }
+
+    /// Next unused client nonce of an account for `pay_to_join` & `register_membership`
+    #[ink(message)]
+    pub fn next_nonce_for(&self, who: AccountId) -> u32 {
+      self.next_client_nonce.get(who).unwrap_or_default()
     }
 
     // TODO renew membership
 
+    /// Questions registrants have to answer when requesting to join
+    #[ink(message)]
+    pub fn join_questionnaire(&self) -> Option<Vec<String>> {
+      self.join_questionnaire.get_or_default()
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn set_join_questionnaire(&mut self, questions: Option<Vec<String>>) -> SpaceResult<()> {
+      if let Some(list) = &questions {
+        ensure!(list.len() <= MAX_JOIN_QUESTIONS, SpaceError::Custom(String::from("Questionnaire has at max 5 questions")));
+        ensure!(
+          list.iter().all(|q| q.len() <= MAX_JOIN_QUESTION_LEN),
+          SpaceError::Custom(String::from("Question is at max 200 chars"))
+        );
+      }
+
+      self.join_questionnaire.set(&questions);
+
+      Ok(())
+    }
+
     /// Register for membership
     #[ink(message, payable)]
-    pub fn register_membership(&mut self, who: Option<AccountId>) -> SpaceResult<()> {
+    pub fn register_membership(&mut self, who: Option<AccountId>, answers: Option<Vec<String>>, referrer: Option<AccountId>, client_nonce: u32) -> SpaceResult<()> {
+      self.ensure_not_paused()?;
+      self.use_client_nonce(client_nonce)?;
+
       let config = self.config();
       ensure!(
         config.registration == RegistrationType::RequestToJoin,
@@ -359,10 +815,8 @@ mod space {
         );
       }
 
-      ensure!(
-        pending_requests.len() as u64 <= MAX_PENDING_REQUESTS,
-        SpaceError::Custom(String::from("Exceeding maximum of pending requests"))
-      );
+      let max_pending_requests = config.max_pending_requests.unwrap_or(MAX_PENDING_REQUESTS).min(MAX_PENDING_REQUESTS);
+      ensure!((pending_requests.len() as u64) < max_pending_requests, SpaceError::PendingRequestsAtCapacity);
 
       let next_request_id = self.requests_nonce.get_or_default().checked_add(1).expect("Exceeding number of requests!");
 
@@ -370,11 +824,21 @@ mod space {
       let valid_payment = match config.pricing {
         Pricing::Free => true,
         Pricing::OneTimePaid { price } => paid_balance >= price,
-        Pricing::Subscription { price, .. } => paid_balance >= price
+        Pricing::Subscription { price, .. } => paid_balance >= price,
+        Pricing::SubscriptionWithToken { token_contract, price, .. } => {
+          self.collect_token_payment(token_contract, Self::env().caller(), price)?;
+          self.request_token_payments.insert(next_request_id, &(token_contract, price));
+          let escrowed = self.escrowed_token_payments.get(token_contract).unwrap_or_default();
+          self.escrowed_token_payments.insert(token_contract, &escrowed.saturating_add(price));
+          true
+        }
       };
 
       ensure!(valid_payment, SpaceError::InsufficientPayment);
 
+      let answers = self.validate_answers(answers)?;
+      self.ensure_valid_referrer(registrant, referrer)?;
+
       self.requests_nonce.set(&next_request_id);
 
       pending_requests.push(next_request_id);
@@ -389,12 +853,129 @@ mod space {
           approved: None,
         },
       );
+      self.escrowed_request_payments.set(&self.escrowed_request_payments.get_or_default().saturating_add(paid_balance));
 
       self.registrant_to_request.insert(registrant, &next_request_id);
 
+      if !answers.is_empty() {
+        self.request_answers.insert(next_request_id, &answers);
+      }
+
+      if let Some(referrer_id) = referrer {
+        self.request_referrers.insert(next_request_id, &referrer_id);
+      }
+
+      Ok(())
+    }
+
+    fn ensure_valid_referrer(&self, registrant: AccountId, referrer: Option<AccountId>) -> SpaceResult<()> {
+      if let Some(referrer_id) = referrer {
+        ensure!(referrer_id != registrant, SpaceError::Custom(String::from("Cannot refer yourself")));
+        ensure!(self.check_active_member(&referrer_id), SpaceError::Custom(String::from("Referrer must be an active member")));
+      }
+
+      Ok(())
+    }
+
+    /// Record referral for a new member, a member can only be referred once
+    fn record_referral(&mut self, who: AccountId, referrer: Option<AccountId>) {
+      let Some(referrer_id) = referrer else { return };
+      if self.referred_by.contains(who) {
+        return;
+      }
+
+      self.referred_by.insert(who, &referrer_id);
+
+      let count = self.referral_count.get(referrer_id).unwrap_or_default().saturating_add(1);
+      self.referral_count.insert(referrer_id, &count);
+
+      let mut recent = self.recent_referrals.get(referrer_id).unwrap_or_default();
+      recent.push(who);
+      if recent.len() > MAX_RECENT_REFERRALS {
+        recent.remove(0);
+      }
+      self.recent_referrals.insert(referrer_id, &recent);
+    }
+
+    /// Who referred a member
+    #[ink(message)]
+    pub fn referred_by(&self, who: AccountId) -> Option<AccountId> {
+      self.referred_by.get(who)
+    }
+
+    /// Referral count and recent referrals of a member
+    #[ink(message)]
+    pub fn referral_stats(&self, who: AccountId) -> SpaceResult<(u32, Vec<AccountId>)> {
+      self.ensure_owner()?;
+
+      Ok((
+        self.referral_count.get(who).unwrap_or_default(),
+        self.recent_referrals.get(who).unwrap_or_default(),
+      ))
+    }
+
+    #[ink(message)]
+    pub fn referral_bonus_per_member(&self) -> Balance {
+      self.referral_bonus_per_member.get_or_default()
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn set_referral_bonus_per_member(&mut self, bonus: Balance) -> SpaceResult<()> {
+      self.referral_bonus_per_member.set(&bonus);
+
+      Ok(())
+    }
+
+    /// Claim bonus for referrals which haven't been claimed yet
+    #[ink(message)]
+    pub fn claim_referral_bonus(&mut self) -> SpaceResult<()> {
+      let caller = self.env().caller();
+
+      let referral_count = self.referral_count.get(caller).unwrap_or_default();
+      let claimed_count = self.claimed_referral_count.get(caller).unwrap_or_default();
+      let unclaimed_count = referral_count.saturating_sub(claimed_count);
+
+      let bonus = self.referral_bonus_per_member().saturating_mul(unclaimed_count as Balance);
+      ensure!(bonus > 0, SpaceError::Custom(String::from("No referral bonus to claim")));
+      ensure!(bonus <= self.treasury_balance(), SpaceError::Custom(String::from("Insufficient treasury balance")));
+
+      self.claimed_referral_count.insert(caller, &referral_count);
+
+      if self.env().transfer(caller, bonus).is_err() {
+        return Err(SpaceError::Custom(String::from("Cannot transfer referral bonus")));
+      }
+
       Ok(())
     }
 
+    fn validate_answers(&self, answers: Option<Vec<String>>) -> SpaceResult<Vec<String>> {
+      match self.join_questionnaire() {
+        Some(questions) => {
+          let answers = answers.unwrap_or_default();
+          ensure!(answers.len() == questions.len(), SpaceError::Custom(String::from("Number of answers must match number of questions")));
+          ensure!(
+            answers.iter().all(|a| a.len() <= MAX_JOIN_ANSWER_LEN),
+            SpaceError::Custom(String::from("Answer is at max 500 chars"))
+          );
+
+          Ok(answers)
+        }
+        None => Ok(Vec::new())
+      }
+    }
+
+    /// Get a membership request along with the registrant's answers to the questionnaire
+    #[ink(message)]
+    pub fn get_request_with_answers(&self, request_id: RequestId) -> SpaceResult<(MembershipRequest, Vec<String>)> {
+      self.ensure_owner()?;
+
+      let request = self.requests.get(request_id).ok_or(SpaceError::Custom(String::from("Request Not Found")))?;
+      let answers = self.request_answers.get(request_id).unwrap_or_default();
+
+      Ok((request, answers))
+    }
+
     /// get number of pending requests
     #[ink(message)]
     pub fn pending_requests_count(&self) -> u64 {
@@ -435,26 +1016,67 @@ mod space {
       let (request_id, request) = maybe_request.unwrap();
 
       // Refund the payment
-      if self.env().transfer(caller, request.paid).is_err() {
+      if !self.refund_request(request_id, &request) {
         return Err(SpaceError::CannotRefundPayment(request.who, request_id));
       }
 
       let mut pending_requests = self.pending_requests.get_or_default();
       pending_requests.retain(|&x| x != request_id);
       self.pending_requests.set(&pending_requests);
+      self.registrant_to_request.remove(caller);
 
       Ok(())
     }
 
-    // Improvements
-    // pub fn get_membership_requests(&self, who: Vec<AccountId>) -> Vec<(AccountId, Option<MembershipRequest>)> {
-    //   let who_to_request: Vec<(AccountId, Option<RequestId>)> = who.iter().map(|x| (x, self.registrant_to_request.get(x))).collect();
-    //   let pending_requests = self.pending_requests.get_or_default();
-    //
-    //   who_to_request.iter().map(|(who, maybe_request_id)| {
-    //     match maybe_request_id {
-    //       None => (who, None),
-    //       Some(request_id) => {
+    /// Cancel pending requests & refund the payments,
+    /// unlike rejection, cancelled requests are dropped without being recorded
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn batch_cancel_requests(&mut self, request_ids: Vec<RequestId>) -> SpaceResult<BatchCancelResult> {
+      let mut pending_requests = self.pending_requests.get_or_default();
+
+      let mut cancelled: u32 = 0;
+      let mut refund_failed: Vec<RequestId> = Vec::new();
+      let mut not_found: u32 = 0;
+
+      for request_id in request_ids {
+        let maybe_request = if pending_requests.contains(&request_id) { self.requests.get(request_id) } else { None };
+
+        match maybe_request {
+          Some(request) => {
+            if !self.refund_request(request_id, &request) {
+              refund_failed.push(request_id);
+              continue;
+            }
+
+            pending_requests.retain(|&x| x != request_id);
+            self.requests.remove(request_id);
+            self.registrant_to_request.remove(request.who);
+            self.request_answers.remove(request_id);
+            self.request_referrers.remove(request_id);
+
+            cancelled = cancelled.saturating_add(1);
+          }
+          None => {
+            not_found = not_found.saturating_add(1);
+          }
+        }
+      }
+
+      self.pending_requests.set(&pending_requests);
+
+      Ok(BatchCancelResult { cancelled, refund_failed, not_found })
+    }
+
+    // Improvements
+    // pub fn get_membership_requests(&self, who: Vec<AccountId>) -> Vec<(AccountId, Option<MembershipRequest>)> {
+    //   let who_to_request: Vec<(AccountId, Option<RequestId>)> = who.iter().map(|x| (x, self.registrant_to_request.get(x))).collect();
+    //   let pending_requests = self.pending_requests.get_or_default();
+    //
+    //   who_to_request.iter().map(|(who, maybe_request_id)| {
+    //     match maybe_request_id {
+    //       None => (who, None),
+    //       Some(request_id) => {
     //         if pending_requests.contains(&request_id) {
     //           (who, Some(self.requests.get(request_id).unwrap()))
     //         } else {
@@ -494,6 +1116,7 @@ mod space {
       let mut approved_count: u32 = 0;
       let mut rejected_count: u32 = 0;
       let mut not_found_count: u32 = 0;
+      let mut welcome_message: Option<String> = None;
 
       let mut submitted_request_ids: Vec<RequestId> = Vec::new();
       for approval in approvals {
@@ -503,9 +1126,17 @@ mod space {
 
           if approved {
             // TODO we should return a list of successful, failed items
-            self.do_grant_membership(request.who, self.profile.config.get_or_default().ttl(), true)?;
+            let maybe_message = self.do_grant_membership(request.who, self.profile.config.get_or_default().ttl(), true)?;
+            if maybe_message.is_some() {
+              welcome_message = maybe_message;
+            }
+
+            self.record_referral(request.who, self.request_referrers.get(request_id));
+            self.release_request_payment(request.paid);
+            self.release_request_token_payment(request_id);
+
             approved_count = approved_count.saturating_add(1);
-          } else if self.env().transfer(request.who, request.paid).is_ok() {
+          } else if self.refund_request(request_id, &request) {
             rejected_count = rejected_count.saturating_add(1);
           } else {
             return Err(SpaceError::CannotRefundPayment(request.who, request_id));
@@ -528,6 +1159,7 @@ mod space {
         approved: approved_count,
         rejected: rejected_count,
         not_found: not_found_count,
+        welcome_message,
       })
     }
 
@@ -539,7 +1171,7 @@ mod space {
     /// Check member status
     #[ink(message)]
     pub fn member_status(&self, who: AccountId) -> MemberStatus {
-      let maybe_member = self.members.get(who);
+      let maybe_member = self.stored_member(who);
       match maybe_member {
         Some(info) => {
           match info.next_renewal_at {
@@ -559,22 +1191,35 @@ mod space {
       }
     }
 
+    /// Earliest time a member who left can join again, None if there is no cooldown
+    #[ink(message)]
+    pub fn rejoin_available_at(&self, who: AccountId) -> Option<Timestamp> {
+      let cooldown = self.config().rejoin_cooldown_secs?;
+      let left_at = self.last_left_at.get(who)?;
+
+      Some(left_at.saturating_add(cooldown))
+    }
+
     /// Active member to leave space
     /// For now, only the member himself can call this
     /// Later we can consider allow owner to do this
     /// or a voting mechanism to force a member to leave
     #[ink(message)]
     pub fn leave(&mut self) -> SpaceResult<()> {
+      self.ensure_not_paused()?;
+
       let who = self.env().caller();
 
-      ensure!(who != Ownable::owner(self).unwrap(), SpaceError::Custom(String::from("Owner cannot leave the space")));
+      ensure!(who != Ownable::owner(self).unwrap(), SpaceError::OwnerCannotLeave);
 
       let member_status = self.member_status(who);
       ensure!(member_status == MemberStatus::Active, SpaceError::NotActiveMember);
-      let mut member_info = self.members.get(who).unwrap();
+      let mut member_info = self.stored_member(who).unwrap();
       member_info.next_renewal_at = Some(0);
 
       self.members.insert(who, &member_info);
+      self.last_left_at.insert(who, &self.env().block_timestamp());
+      self.non_left_members_count.set(&self.members_count().saturating_sub(1));
 
       // Remove space member tracking
       let _ = build_call::<DefaultEnvironment>()
@@ -584,14 +1229,181 @@ mod space {
           ExecutionInput::new(Selector::new(ink::selector_bytes!("remove_space_member")))
             .push_arg(who)
         )
-        .returns::<SpaceResult<()>>()
+        .returns::<MotherSpaceResult<()>>()
         .invoke();
 
       Ok(())
     }
 
+    #[ink(message)]
+    pub fn follow_member(&mut self, who: AccountId) -> SpaceResult<()> {
+      let caller = self.env().caller();
+
+      ensure!(self.check_active_member(&caller), SpaceError::NotActiveMember);
+      ensure!(caller != who, SpaceError::Custom(String::from("Cannot follow yourself")));
+      ensure!(self.is_member(Some(who)), SpaceError::MemberNotFound);
+      ensure!(!self.is_following(caller, who), SpaceError::Custom(String::from("Already following this member")));
+
+      self.following.insert((caller, who), &true);
+
+      let mut following_ids = self.following_ids.get(caller).unwrap_or_default();
+      following_ids.push(who);
+      self.following_ids.insert(caller, &following_ids);
+
+      self.follower_count.insert(who, &self.follower_count(who).saturating_add(1));
+      self.following_count.insert(caller, &self.following_count(caller).saturating_add(1));
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn unfollow_member(&mut self, who: AccountId) -> SpaceResult<()> {
+      let caller = self.env().caller();
+      ensure!(self.is_following(caller, who), SpaceError::Custom(String::from("Not following this member")));
+
+      self.following.remove((caller, who));
+
+      let mut following_ids = self.following_ids.get(caller).unwrap_or_default();
+      following_ids.retain(|&x| x != who);
+      self.following_ids.insert(caller, &following_ids);
+
+      self.follower_count.insert(who, &self.follower_count(who).saturating_sub(1));
+      self.following_count.insert(caller, &self.following_count(caller).saturating_sub(1));
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    pub fn is_following(&self, follower: AccountId, followee: AccountId) -> bool {
+      self.following.get((follower, followee)).unwrap_or_default()
+    }
+
+    #[ink(message)]
+    pub fn follower_count(&self, who: AccountId) -> u32 {
+      self.follower_count.get(who).unwrap_or_default()
+    }
+
+    #[ink(message)]
+    pub fn following_count(&self, who: AccountId) -> u32 {
+      self.following_count.get(who).unwrap_or_default()
+    }
+
+    /// List members followed by an account
+    #[ink(message)]
+    pub fn following_list(&self, who: AccountId, from: u32, per_page: u32) -> MembersPage {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let following_ids = self.following_ids.get(who).unwrap_or_default();
+      let last_position = from.saturating_add(per_page);
+      let total = following_ids.len() as u32;
+
+      let mut member_records = Vec::new();
+      for index in (from as usize)..(last_position.min(total) as usize) {
+        let account_id = following_ids[index];
+        if let Some(info) = self.stored_member(account_id) {
+          member_records.push(MemberRecord { index: index as u32, account_id, info })
+        }
+      }
+
+      MembersPage {
+        items: member_records,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      }
+    }
+
+    /// Deposit to the space treasury
+    #[ink(message, payable)]
+    pub fn deposit_to_treasury(&mut self) -> SpaceResult<()> {
+      ensure!(self.check_active_member(&self.env().caller()), SpaceError::NotActiveMember);
+      ensure!(self.env().transferred_value() > 0, SpaceError::Custom(String::from("Deposit amount must be greater than 0")));
+
+      Ok(())
+    }
+
+    /// PSP22 token fees available to withdraw, token payments of pending requests are excluded
+    #[ink(message)]
+    pub fn token_fees_balance(&self, token: AccountId) -> Balance {
+      let space_balance = build_call::<DefaultEnvironment>()
+        .call(token)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::balance_of")))
+            .push_arg(self.env().account_id())
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()
+        .and_then(|rs| rs.ok())
+        .unwrap_or_default();
+
+      space_balance.saturating_sub(self.escrowed_token_payments.get(token).unwrap_or_default())
+    }
+
+    /// Withdraw PSP22 token fees collected from token subscriptions
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn withdraw_token_fees(&mut self, token: AccountId, amount: Balance, to: AccountId) -> SpaceResult<()> {
+      ensure!(amount <= self.token_fees_balance(token), SpaceError::Custom(String::from("Insufficient token fees")));
+      ensure!(self.transfer_token(token, to, amount), SpaceError::Custom(String::from("Cannot withdraw token fees")));
+
+      Ok(())
+    }
+
+    #[ink(message)]
+    #[modifiers(only_owner)]
+    pub fn withdraw_from_treasury(&mut self, amount: Balance, to: AccountId, memo: String) -> SpaceResult<()> {
+      ensure!(memo.len() <= MAX_WITHDRAWAL_MEMO_LEN, SpaceError::Custom(String::from("Memo is at max 200 chars")));
+      ensure!(amount <= self.treasury_balance(), SpaceError::Custom(String::from("Insufficient treasury balance")));
+
+      if self.env().transfer(to, amount).is_err() {
+        return Err(SpaceError::Custom(String::from("Cannot withdraw from treasury")));
+      }
+
+      let withdrawal_id = self.withdrawal_count.get_or_default();
+      self.treasury_withdrawals.insert(withdrawal_id, &TreasuryWithdrawal {
+        amount,
+        to,
+        memo,
+        at: self.env().block_timestamp(),
+        by: self.env().caller(),
+      });
+      self.withdrawal_count.set(&withdrawal_id.checked_add(1).expect("Exceeds number of withdrawals"));
+
+      Ok(())
+    }
+
+    /// Withdrawable balance, payments of pending requests & the existential deposit are excluded
+    #[ink(message)]
+    pub fn treasury_balance(&self) -> Balance {
+      self.env().balance()
+        .saturating_sub(self.escrowed_request_payments.get_or_default())
+        .saturating_sub(self.env().minimum_balance())
+    }
+
+    #[ink(message)]
+    pub fn treasury_withdrawal_history(&self, from: u32, per_page: u32) -> Vec<TreasuryWithdrawal> {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let withdrawal_count = self.withdrawal_count.get_or_default();
+
+      ((from as usize)..(last_position.min(withdrawal_count) as usize))
+        .filter_map(|index| self.treasury_withdrawals.get(index as u32))
+        .collect()
+    }
+
+    /// Members stored in the legacy layout are read without profile fields
+    fn stored_member(&self, who: AccountId) -> Option<MemberInfo> {
+      let key = (StorageKey::key(&self.members), who);
+      match ink::env::get_contract_storage::<(Key, AccountId), MemberInfo>(&key) {
+        Ok(maybe_info) => maybe_info,
+        Err(_) => ink::env::get_contract_storage::<(Key, AccountId), LegacyMemberInfo>(&key).ok().flatten().map(MemberInfo::from),
+      }
+    }
+
     fn check_active_member(&self, id: &AccountId) -> bool {
-      let maybe_member = self.members.get(id);
+      let maybe_member = self.stored_member(*id);
       match maybe_member {
         Some(info) => {
           match info.next_renewal_at {
@@ -612,11 +1424,34 @@ mod space {
     /// Member info
     #[ink(message)]
     pub fn member_info(&self, who: AccountId) -> Option<MemberInfo> {
-      self.members.get(who)
+      self.stored_member(who)
+    }
+
+    /// Display names of members, at max 50 accounts
+    #[ink(message)]
+    pub fn member_names(&self, who: Vec<AccountId>) -> Vec<(AccountId, Option<String>)> {
+      who.into_iter()
+        .take(50)
+        .map(|account_id| (account_id, self.stored_member(account_id).and_then(|info| info.name)))
+        .collect()
     }
 
+    /// Member profile without membership timestamps
     #[ink(message)]
-    pub fn update_member_info(&mut self, name: Option<String>) -> SpaceResult<()> {
+    pub fn member_info_public(&self, who: AccountId) -> Option<MemberInfoPublic> {
+      self.stored_member(who).map(|info| MemberInfoPublic {
+        name: info.name,
+        bio: info.bio,
+        avatar: info.avatar,
+        website: info.website,
+        skills: info.skills,
+      })
+    }
+
+    #[ink(message)]
+    pub fn update_member_info(&mut self, name: Option<String>, bio: Option<String>,
+                              avatar: Option<ImageSource>, website: Option<String>,
+                              skills: Option<Vec<String>>) -> SpaceResult<()> {
       let caller = self.env().caller();
 
       ensure!(self.check_active_member(&caller), SpaceError::NotActiveMember);
@@ -625,20 +1460,319 @@ mod space {
         ensure!(new_name.len() <= 30, SpaceError::Custom(String::from("Display name must be at most 30 characters")));
       }
 
-      let updated_member_info = self
-        .members
-        .get(caller)
-        .map(|member_info| MemberInfo {
-          name,
-          ..member_info
-        })
-        .unwrap();
+      if let Some(new_bio) = &bio {
+        ensure!(new_bio.len() <= MAX_MEMBER_BIO_LEN, SpaceError::Custom(String::from("Bio must be at most 200 characters")));
+      }
+
+      if let Some(new_avatar) = &avatar {
+        new_avatar.validate()?;
+      }
+
+      if let Some(new_website) = &website {
+        ensure!(new_website.len() <= MAX_MEMBER_WEBSITE_LEN, SpaceError::Custom(String::from("Website must be at most 200 characters")));
+        ensure!(new_website.starts_with("https://"), SpaceError::Custom(String::from("Website must be a https url")));
+      }
+
+      if let Some(new_skills) = &skills {
+        ensure!(new_skills.len() <= MAX_MEMBER_SKILLS, SpaceError::Custom(String::from("Skills must be at most 10 items")));
+        ensure!(
+          new_skills.iter().all(|skill| skill.len() >= 3 && skill.len() <= 30 && skill.is_ascii()),
+          SpaceError::Custom(String::from("Skill must be from 3 to 30 ascii characters"))
+        );
+      }
+
+      let member_info = self.stored_member(caller).unwrap();
+      self.update_skills_index(caller, member_info.skills.clone().unwrap_or_default(), skills.clone().unwrap_or_default())?;
+
+      let updated_member_info = MemberInfo {
+        name,
+        bio,
+        avatar,
+        website,
+        skills,
+        ..member_info
+      };
 
       self.members.insert(caller, &updated_member_info);
 
       Ok(())
     }
 
+    fn update_skills_index(&mut self, who: AccountId, old_skills: Vec<String>, new_skills: Vec<String>) -> SpaceResult<()> {
+      let mut all_skills = self.all_skills.get_or_default();
+
+      for skill in old_skills.iter().filter(|&skill| !new_skills.contains(skill)) {
+        let mut members = self.skill_to_members.get(skill).unwrap_or_default();
+        members.retain(|&x| x != who);
+
+        if members.is_empty() {
+          self.skill_to_members.remove(skill);
+          all_skills.retain(|x| x != skill);
+        } else {
+          self.skill_to_members.insert(skill, &members);
+        }
+      }
+
+      for skill in new_skills.iter().filter(|&skill| !old_skills.contains(skill)) {
+        let mut members = self.skill_to_members.get(skill).unwrap_or_default();
+        if !members.contains(&who) {
+          members.push(who);
+          self.skill_to_members.insert(skill, &members);
+        }
+
+        if !all_skills.contains(skill) {
+          all_skills.push(skill.clone());
+        }
+      }
+
+      ensure!(all_skills.len() <= MAX_SPACE_SKILLS, SpaceError::Custom(String::from("Space has too many skill tags")));
+      self.all_skills.set(&all_skills);
+
+      Ok(())
+    }
+
+    /// Members having a skill tag
+    #[ink(message)]
+    pub fn search_members_by_skill(&self, skill: String, from: u32, per_page: u32) -> MembersPage {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+      let last_position = from.saturating_add(per_page);
+      let skilled_members = self.skill_to_members.get(skill).unwrap_or_default();
+      let total = skilled_members.len() as u32;
+
+      let member_records = skilled_members
+        .into_iter()
+        .skip(from as usize)
+        .take(per_page as usize)
+        .filter_map(|account_id| {
+          let index = self.member_indexes.get(account_id)?;
+          self.stored_member(account_id).map(|info| MemberRecord { index, account_id, info })
+        })
+        .collect();
+
+      MembersPage {
+        items: member_records,
+        from,
+        per_page,
+        has_next_page: last_position < total,
+        total,
+      }
+    }
+
+    /// Skill tags of members in the space
+    #[ink(message)]
+    pub fn all_skills(&self, from: u32, per_page: u32) -> Vec<String> {
+      let per_page = per_page.min(50); // limit per page at max 50 items
+
+      self.all_skills.get_or_default()
+        .into_iter()
+        .skip(from as usize)
+        .take(per_page as usize)
+        .collect()
+    }
+
+    /// Check global pause state of motherspace, falls back to the cached state if motherspace fails to respond
+    fn is_globally_paused(&self) -> bool {
+      build_call::<DefaultEnvironment>()
+        .call(self.motherspace_id())
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("is_globally_paused")))
+        )
+        .returns::<bool>()
+        .try_invoke()
+        .ok()
+        .and_then(|rs| rs.ok())
+        .unwrap_or_else(|| self.pause_cache.get_or_default())
+    }
+
+    fn ensure_not_paused(&self) -> SpaceResult<()> {
+      // members are migrated in batches, membership changes in between would be counted twice or missed
+      ensure!(self.migration_cursor.get_or_default() == 0, SpaceError::Custom(String::from("Migration in progress")));
+      ensure!(!self.is_globally_paused(), SpaceError::Custom(String::from("System is paused")));
+
+      Ok(())
+    }
+
+    /// Sync the cached global pause state with motherspace
+    #[ink(message)]
+    pub fn refresh_pause_cache(&mut self) -> SpaceResult<bool> {
+      let paused = self.is_globally_paused();
+      self.pause_cache.set(&paused);
+
+      Ok(paused)
+    }
+
+    // expired, left & non-expiring members are excluded
+    fn is_expiring_soon(&self, info: &MemberInfo, within_secs: u64) -> bool {
+      let now = Self::env().block_timestamp();
+      let remaining = info.next_renewal_at.unwrap_or(u64::MAX).saturating_sub(now);
+
+      info.next_renewal_at.is_some() && remaining > 0 && remaining <= within_secs
+    }
+
+    /// Transfer `price` of `token` from `payer` to the space, payer must approve the space beforehand
+    fn collect_token_payment(&mut self, token: AccountId, payer: AccountId, price: Balance) -> SpaceResult<()> {
+      let space_id = self.env().account_id();
+
+      let allowance = build_call::<DefaultEnvironment>()
+        .call(token)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::allowance")))
+            .push_arg(payer)
+            .push_arg(space_id)
+        )
+        .returns::<Balance>()
+        .invoke();
+
+      ensure!(allowance >= price, SpaceError::InsufficientPayment);
+
+      let result = build_call::<DefaultEnvironment>()
+        .call(token)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer_from")))
+            .push_arg(payer)
+            .push_arg(space_id)
+            .push_arg(price)
+            .push_arg(Vec::<u8>::new())
+        )
+        .returns::<Result<(), PSP22Error>>()
+        .invoke();
+
+      ensure!(result.is_ok(), SpaceError::Custom(String::from("Token payment failed")));
+
+      Ok(())
+    }
+
+    fn transfer_token(&mut self, token: AccountId, to: AccountId, amount: Balance) -> bool {
+      let result = build_call::<DefaultEnvironment>()
+        .call(token)
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+            .push_arg(to)
+            .push_arg(amount)
+            .push_arg(Vec::<u8>::new())
+        )
+        .returns::<Result<(), PSP22Error>>()
+        .try_invoke();
+
+      matches!(result, Ok(Ok(Ok(()))))
+    }
+
+    /// Refund token & native payments of a request.
+    /// Tokens go first, a failed token refund leaves the native payment in place for a retry,
+    /// refunded tokens are released so a retry after a failed native refund does not send them again
+    fn refund_request(&mut self, request_id: RequestId, request: &MembershipRequest) -> bool {
+      if let Some((token, amount)) = self.request_token_payments.get(request_id) {
+        if !self.transfer_token(token, request.who, amount) {
+          return false;
+        }
+
+        self.release_request_token_payment(request_id);
+      }
+
+      if self.env().transfer(request.who, request.paid).is_err() {
+        return false;
+      }
+      self.release_request_payment(request.paid);
+
+      true
+    }
+
+    fn release_request_payment(&mut self, paid: Balance) {
+      self.escrowed_request_payments.set(&self.escrowed_request_payments.get_or_default().saturating_sub(paid));
+    }
+
+    fn release_request_token_payment(&mut self, request_id: RequestId) {
+      if let Some((token, amount)) = self.request_token_payments.take(request_id) {
+        let escrowed = self.escrowed_token_payments.get(token).unwrap_or_default();
+        self.escrowed_token_payments.insert(token, &escrowed.saturating_sub(amount));
+      }
+    }
+
+    fn use_client_nonce(&mut self, client_nonce: u32) -> SpaceResult<()> {
+      let caller = self.env().caller();
+      ensure!(!self.used_nonces.contains((caller, client_nonce)), SpaceError::Custom(String::from("Nonce already used")));
+
+      self.used_nonces.insert((caller, client_nonce), &true);
+      if client_nonce >= self.next_nonce_for(caller) {
+        self.next_client_nonce.insert(caller, &client_nonce.saturating_add(1));
+      }
+
+      Ok(())
+    }
+
+    fn has_left(info: &MemberInfo) -> bool {
+      info.next_renewal_at == Some(0)
+    }
+
+    fn increase_non_left_members_count(&mut self) {
+      let count = self.members_count().checked_add(1).expect("Exceeds number of members");
+      self.non_left_members_count.set(&count);
+    }
+
+    fn migrate_non_left_members_count(&mut self) -> bool {
+      let (from, to) = self.migration_batch();
+
+      let mut count: u32 = if from == 0 { 0 } else { self.non_left_members_count.get_or_default() };
+      for index in from..to {
+        if let Some(info) = self.index_to_member.get(index).and_then(|account_id| self.stored_member(account_id)) {
+          if !Self::has_left(&info) {
+            count = count.saturating_add(1);
+          }
+        }
+      }
+
+      self.non_left_members_count.set(&count);
+      self.complete_migration_batch(to)
+    }
+
+    /// Next batch of member indexes to migrate
+    fn migration_batch(&self) -> (u32, u32) {
+      let from = self.migration_cursor.get_or_default();
+
+      (from, from.saturating_add(MIGRATION_BATCH_SIZE).min(self.members_nonce.get_or_default()))
+    }
+
+    /// Move the migration cursor past a batch, returns true and resets the cursor once all members are migrated
+    fn complete_migration_batch(&mut self, to: u32) -> bool {
+      if to >= self.members_nonce.get_or_default() {
+        self.migration_cursor.set(&0);
+        true
+      } else {
+        self.migration_cursor.set(&to);
+        false
+      }
+    }
+
+    fn migrate_member_indexes(&mut self) -> bool {
+      let (from, to) = self.migration_batch();
+      for index in from..to {
+        if let Some(account_id) = self.index_to_member.get(index) {
+          self.member_indexes.insert(account_id, &index);
+        }
+      }
+
+      self.complete_migration_batch(to)
+    }
+
+    fn migrate_escrowed_request_payments(&mut self) {
+      let escrowed = self.pending_requests.get_or_default()
+        .iter()
+        .filter_map(|&request_id| self.requests.get(request_id))
+        .fold(0, |total: Balance, request| total.saturating_add(request.paid));
+
+      self.escrowed_request_payments.set(&escrowed);
+    }
+
+    fn ensure_owner(&self) -> SpaceResult<()> {
+      ensure!(Ownable::owner(self) == Some(self.env().caller()), SpaceError::OwnableError(OwnableError::CallerIsNotOwner));
+
+      Ok(())
+    }
+
     fn is_member(&self, who: Option<AccountId>) -> bool {
       let who = who.unwrap_or(self.env().caller());
       let member_status = self.member_status(who);
@@ -646,4 +1780,749 @@ mod space {
       member_status == MemberStatus::Active || member_status == MemberStatus::Inactive
     }
   }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use ink::env::test;
+
+    const TREASURY_BALANCE: Balance = 1_000;
+
+    /// Space deployed by alice acting as motherspace & owner, funded with the minimum balance plus `TREASURY_BALANCE`
+    fn new_space(config: Option<SpaceConfig>) -> Space {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let space_id = AccountId::from([0xFF; 32]);
+      test::set_callee::<DefaultEnvironment>(space_id);
+      test::set_caller::<DefaultEnvironment>(accounts.alice);
+
+      let info = SpaceInfo { name: String::from("InSpace"), desc: None, logo: None };
+      let space = Space::new(accounts.alice, accounts.alice, info, config).expect("Cannot create space");
+
+      let minimum_balance = ink::env::minimum_balance::<DefaultEnvironment>();
+      test::set_account_balance::<DefaultEnvironment>(space_id, minimum_balance + TREASURY_BALANCE);
+
+      space
+    }
+
+    #[ink::test]
+    fn deposit_to_treasury_works() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      test::set_account_balance::<DefaultEnvironment>(accounts.alice, 500);
+      test::transfer_in::<DefaultEnvironment>(100);
+      assert!(space.deposit_to_treasury().is_ok());
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE + 100);
+    }
+
+    #[ink::test]
+    fn deposit_to_treasury_requires_active_member() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      test::set_caller::<DefaultEnvironment>(accounts.bob);
+      test::set_account_balance::<DefaultEnvironment>(accounts.bob, 500);
+      test::transfer_in::<DefaultEnvironment>(100);
+      assert!(matches!(space.deposit_to_treasury(), Err(SpaceError::NotActiveMember)));
+    }
+
+    #[ink::test]
+    fn withdraw_from_treasury_works() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      let charlie_balance = test::get_account_balance::<DefaultEnvironment>(accounts.charlie).unwrap_or_default();
+
+      assert!(space.withdraw_from_treasury(400, accounts.charlie, String::from("Event prizes")).is_ok());
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE - 400);
+      assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.charlie), Ok(charlie_balance + 400));
+
+      let history = space.treasury_withdrawal_history(0, 10);
+      assert_eq!(history.len(), 1);
+      assert_eq!(history[0].amount, 400);
+      assert_eq!(history[0].to, accounts.charlie);
+    }
+
+    #[ink::test]
+    fn withdraw_from_treasury_keeps_minimum_balance() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      let result = space.withdraw_from_treasury(TREASURY_BALANCE + 1, accounts.charlie, String::new());
+      assert!(matches!(result, Err(SpaceError::Custom(_))));
+      assert!(space.withdraw_from_treasury(TREASURY_BALANCE, accounts.charlie, String::new()).is_ok());
+      assert_eq!(space.treasury_balance(), 0);
+    }
+
+    #[ink::test]
+    fn withdraw_from_treasury_keeps_pending_request_payments() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      space.escrowed_request_payments.set(&300);
+
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE - 300);
+      let result = space.withdraw_from_treasury(TREASURY_BALANCE - 299, accounts.charlie, String::new());
+      assert!(matches!(result, Err(SpaceError::Custom(_))));
+
+      space.release_request_payment(300);
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE);
+    }
+
+    #[ink::test]
+    fn withdraw_from_treasury_requires_owner() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      test::set_caller::<DefaultEnvironment>(accounts.bob);
+      let result = space.withdraw_from_treasury(100, accounts.bob, String::new());
+      assert!(matches!(result, Err(SpaceError::OwnableError(OwnableError::CallerIsNotOwner))));
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE);
+    }
+
+    #[ink::test]
+    fn expiring_soon_count_works() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      space.do_grant_membership(accounts.bob, Some(100), false).unwrap();
+      space.do_grant_membership(accounts.charlie, Some(1_000), false).unwrap();
+      space.do_grant_membership(accounts.django, None, false).unwrap();
+      space.do_grant_membership(accounts.eve, Some(10), false).unwrap();
+
+      // bob expires in exactly 50 secs, eve has already expired
+      test::set_block_timestamp::<DefaultEnvironment>(1_050);
+      assert_eq!(space.expiring_soon_count(49, 0, 50), 0);
+      assert_eq!(space.expiring_soon_count(50, 0, 50), 1);
+      assert_eq!(space.expiring_soon_count(1_000, 0, 50), 2);
+
+      // non-expiring members never count
+      assert_eq!(space.expiring_soon_count(u64::MAX, 0, 50), 2);
+    }
+
+    #[ink::test]
+    fn expiring_soon_count_is_paginated() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      space.do_grant_membership(accounts.bob, Some(100), false).unwrap();
+      space.do_grant_membership(accounts.charlie, Some(100), false).unwrap();
+
+      // owner at index 0 & bob at index 1
+      assert_eq!(space.expiring_soon_count(100, 0, 2), 1);
+      assert_eq!(space.expiring_soon_count(100, 2, 2), 1);
+      assert_eq!(space.expiring_soon_count(100, 3, 2), 0);
+    }
+
+    #[ink::test]
+    fn update_member_info_validates_max_lengths() {
+      let mut space = new_space(None);
+      let too_long = "a".repeat(201);
+
+      let result = space.update_member_info(None, Some(too_long.clone()), None, None, None);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Bio must be at most 200 characters"));
+
+      let website = format!("https://{}", too_long);
+      let result = space.update_member_info(None, None, None, Some(website), None);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Website must be at most 200 characters"));
+
+      assert!(space.update_member_info(None, Some("a".repeat(200)), None, None, None).is_ok());
+    }
+
+    #[ink::test]
+    fn update_member_info_requires_https_website() {
+      let mut space = new_space(None);
+
+      for website in ["http://coongcrafts.io", "coongcrafts.io", "ftp://coongcrafts.io"] {
+        let result = space.update_member_info(None, None, None, Some(String::from(website)), None);
+        assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Website must be a https url"));
+      }
+
+      assert!(space.update_member_info(None, None, None, Some(String::from("https://coongcrafts.io")), None).is_ok());
+    }
+
+    #[ink::test]
+    fn update_member_info_keeps_membership_fields() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      let before = space.member_info(accounts.alice).expect("Alice is a member");
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      let result = space.update_member_info(
+        Some(String::from("Alice")),
+        Some(String::from("Builder")),
+        None,
+        Some(String::from("https://coongcrafts.io")),
+        None,
+      );
+      assert!(result.is_ok());
+
+      let after = space.member_info(accounts.alice).expect("Alice is a member");
+      assert_eq!(after.name, Some(String::from("Alice")));
+      assert_eq!(after.bio, Some(String::from("Builder")));
+      assert_eq!(after.website, Some(String::from("https://coongcrafts.io")));
+      assert_eq!(after.joined_at, before.joined_at);
+      assert_eq!(after.next_renewal_at, before.next_renewal_at);
+    }
+
+    #[ink::test]
+    fn legacy_member_info_is_readable() {
+      use scale::{Decode, Encode};
+
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let space = new_space(None);
+      let legacy = LegacyMemberInfo { name: Some(String::from("Bob")), next_renewal_at: Some(5_000), joined_at: 1_000 };
+      assert!(MemberInfo::decode(&mut &legacy.encode()[..]).is_err());
+
+      ink::env::set_contract_storage(&(StorageKey::key(&space.members), accounts.bob), &legacy);
+      let info = space.member_info(accounts.bob).expect("Cannot read legacy member");
+      assert_eq!(info.name, Some(String::from("Bob")));
+      assert_eq!(info.next_renewal_at, Some(5_000));
+      assert_eq!(info.joined_at, 1_000);
+      assert_eq!(info.bio, None);
+      assert_eq!(info.website, None);
+      assert_eq!(info.skills, None);
+    }
+
+    fn set_skills(space: &mut Space, who: AccountId, skills: &[&str]) {
+      test::set_caller::<DefaultEnvironment>(who);
+      let skills = skills.iter().map(|&skill| String::from(skill)).collect();
+      space.update_member_info(None, None, None, None, Some(skills)).expect("Cannot update skills");
+    }
+
+    #[ink::test]
+    fn skills_index_follows_member_updates() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      space.do_grant_membership(accounts.bob, None, false).unwrap();
+      space.do_grant_membership(accounts.charlie, None, false).unwrap();
+
+      set_skills(&mut space, accounts.bob, &["rust", "design"]);
+      set_skills(&mut space, accounts.charlie, &["rust"]);
+      assert_eq!(space.all_skills(0, 10), vec![String::from("rust"), String::from("design")]);
+
+      // records carry member indexes, alice is at index 0
+      let page = space.search_members_by_skill(String::from("rust"), 0, 10);
+      assert_eq!(page.total, 2);
+      assert_eq!(page.items.iter().map(|record| (record.index, record.account_id)).collect::<Vec<_>>(), vec![(1, accounts.bob), (2, accounts.charlie)]);
+
+      // unused skills are pruned
+      set_skills(&mut space, accounts.bob, &["ink"]);
+      assert_eq!(space.all_skills(0, 10), vec![String::from("rust"), String::from("ink")]);
+      assert_eq!(space.search_members_by_skill(String::from("design"), 0, 10).total, 0);
+      let page = space.search_members_by_skill(String::from("rust"), 0, 10);
+      assert_eq!(page.items.iter().map(|record| record.account_id).collect::<Vec<_>>(), vec![accounts.charlie]);
+
+      set_skills(&mut space, accounts.charlie, &[]);
+      assert_eq!(space.all_skills(0, 10), vec![String::from("ink")]);
+    }
+
+    #[ink::test]
+    fn search_members_by_skill_is_paginated() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      for who in [accounts.bob, accounts.charlie, accounts.django] {
+        space.do_grant_membership(who, None, false).unwrap();
+        set_skills(&mut space, who, &["rust"]);
+      }
+
+      let page = space.search_members_by_skill(String::from("rust"), 0, 2);
+      assert_eq!(page.items.len(), 2);
+      assert!(page.has_next_page);
+      assert_eq!(page.total, 3);
+
+      let page = space.search_members_by_skill(String::from("rust"), 2, 2);
+      assert_eq!(page.items.len(), 1);
+      assert_eq!(page.items[0].index, 3);
+      assert_eq!(page.items[0].account_id, accounts.django);
+      assert!(!page.has_next_page);
+
+      assert_eq!(space.all_skills(1, 10), Vec::<String>::new());
+    }
+
+    #[ink::test]
+    fn migrate_member_indexes_runs_in_batches() {
+      let mut space = new_space(None);
+      for seed in 0x10..0xA5u8 {
+        space.do_grant_membership(AccountId::from([seed; 32]), None, false).unwrap();
+      }
+
+      // members stored before member indexes were tracked
+      for index in 0..150 {
+        let account_id = space.index_to_member.get(index).unwrap();
+        space.member_indexes.remove(account_id);
+      }
+      space._set_storage_version(6);
+
+      assert_eq!(space.migrate().ok(), Some(6));
+      assert!(space.member_indexes.contains(space.index_to_member.get(99).unwrap()));
+      assert!(!space.member_indexes.contains(space.index_to_member.get(100).unwrap()));
+
+      assert_eq!(space.migrate().ok(), Some(7));
+      for index in 0..150 {
+        assert_eq!(space.member_indexes.get(space.index_to_member.get(index).unwrap()), Some(index));
+      }
+    }
+
+    /// Same storage changes as a paid `register_membership`, which cannot be called off-chain
+    fn seed_request(space: &mut Space, who: AccountId, paid: Balance) -> RequestId {
+      let request_id = space.requests_nonce.get_or_default() + 1;
+      space.requests_nonce.set(&request_id);
+
+      let mut pending_requests = space.pending_requests.get_or_default();
+      pending_requests.push(request_id);
+      space.pending_requests.set(&pending_requests);
+
+      space.requests.insert(request_id, &MembershipRequest { who, paid, requested_at: 0, approved: None });
+      space.escrowed_request_payments.set(&space.escrowed_request_payments.get_or_default().saturating_add(paid));
+      space.registrant_to_request.insert(who, &request_id);
+
+      request_id
+    }
+
+    fn pending_request_members(space: &Space) -> Vec<AccountId> {
+      space.pending_requests(0, 50).items.iter().map(|request| request.who).collect()
+    }
+
+    #[ink::test]
+    fn batch_cancel_requests_refunds_all() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      let bob_request = seed_request(&mut space, accounts.bob, 100);
+      let charlie_request = seed_request(&mut space, accounts.charlie, 100);
+      let django_request = seed_request(&mut space, accounts.django, 100);
+      let bob_balance = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+
+      let result = space.batch_cancel_requests(vec![bob_request, charlie_request, django_request]).unwrap();
+      assert_eq!(result.cancelled, 3);
+      assert!(result.refund_failed.is_empty());
+      assert_eq!(result.not_found, 0);
+
+      assert!(pending_request_members(&space).is_empty());
+      assert!(space.pending_request_for(Some(accounts.bob)).is_none());
+      assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(), bob_balance + 100);
+      // cancelled requests are dropped, not recorded
+      assert!(space.requests.get(bob_request).is_none());
+    }
+
+    #[ink::test]
+    fn batch_cancel_requests_skips_unknown_ids() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      let bob_request = seed_request(&mut space, accounts.bob, 100);
+      let charlie_request = seed_request(&mut space, accounts.charlie, 100);
+
+      let result = space.batch_cancel_requests(vec![bob_request, 99]).unwrap();
+      assert_eq!(result.cancelled, 1);
+      assert!(result.refund_failed.is_empty());
+      assert_eq!(result.not_found, 1);
+      assert_eq!(pending_request_members(&space), vec![accounts.charlie]);
+
+      // already cancelled requests are not found anymore
+      let result = space.batch_cancel_requests(vec![bob_request, charlie_request]).unwrap();
+      assert_eq!(result.cancelled, 1);
+      assert_eq!(result.not_found, 1);
+      assert_eq!(space.pending_requests_count(), 0);
+    }
+
+    /// Same storage changes as `leave`, which cannot be called off-chain
+    fn mark_left(space: &mut Space, who: AccountId) {
+      let mut info = space.stored_member(who).unwrap();
+      info.next_renewal_at = Some(0);
+      space.members.insert(who, &info);
+      space.last_left_at.insert(who, &ink::env::block_timestamp::<DefaultEnvironment>());
+      space.non_left_members_count.set(&space.members_count().saturating_sub(1));
+    }
+
+    #[ink::test]
+    fn migrate_non_left_members_count_runs_in_batches() {
+      let mut space = new_space(None);
+      for seed in 0x10..0xA5u8 {
+        space.do_grant_membership(AccountId::from([seed; 32]), None, false).unwrap();
+      }
+      for seed in (0x10..0xA5u8).step_by(10) {
+        mark_left(&mut space, AccountId::from([seed; 32]));
+      }
+      assert_eq!(space.members_count(), 135);
+
+      // count is rebuilt from scratch
+      space.non_left_members_count.set(&0);
+      space._set_storage_version(3);
+
+      assert_eq!(space.migrate().ok(), Some(3));
+      assert!(matches!(space.ensure_not_paused(), Err(SpaceError::Custom(message)) if message == "Migration in progress"));
+
+      assert_eq!(space.migrate().ok(), Some(4));
+      assert_eq!(space.members_count(), 135);
+      assert_eq!(space.migration_cursor.get_or_default(), 0);
+    }
+
+    #[ink::test]
+    fn list_members_can_skip_left_members() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      for who in [accounts.bob, accounts.charlie, accounts.django] {
+        space.do_grant_membership(who, None, false).unwrap();
+      }
+      mark_left(&mut space, accounts.charlie);
+
+      let all_members = space.list_members(0, 10, true);
+      assert_eq!(all_members.total, 4);
+      assert_eq!(
+        all_members.items.iter().map(|record| record.account_id).collect::<Vec<_>>(),
+        vec![accounts.alice, accounts.bob, accounts.charlie, accounts.django]
+      );
+
+      let non_left_members = space.list_members(0, 10, false);
+      assert_eq!(non_left_members.total, 3);
+      assert_eq!(
+        non_left_members.items.iter().map(|record| record.account_id).collect::<Vec<_>>(),
+        vec![accounts.alice, accounts.bob, accounts.django]
+      );
+      // left members keep their index
+      assert_eq!(non_left_members.items[2].index, 3);
+    }
+
+    #[ink::test]
+    fn list_members_page_is_clamped() {
+      let mut space = new_space(None);
+      for seed in 0x10..0x4Fu8 {
+        space.do_grant_membership(AccountId::from([seed; 32]), None, false).unwrap();
+      }
+
+      let page = space.list_members(0, 100, true);
+      assert_eq!(page.per_page, 50);
+      assert_eq!(page.items.len(), 50);
+      assert!(page.has_next_page);
+      assert_eq!(page.total, 64);
+    }
+
+    #[ink::test]
+    fn referrals_are_counted() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      space.record_referral(accounts.bob, Some(accounts.alice));
+      space.record_referral(accounts.charlie, Some(accounts.alice));
+      space.record_referral(accounts.django, None);
+
+      assert_eq!(space.referred_by(accounts.bob), Some(accounts.alice));
+      assert_eq!(space.referred_by(accounts.django), None);
+      assert_eq!(space.referral_stats(accounts.alice).ok(), Some((2, vec![accounts.bob, accounts.charlie])));
+    }
+
+    #[ink::test]
+    fn member_is_referred_once() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      space.record_referral(accounts.bob, Some(accounts.alice));
+      space.record_referral(accounts.bob, Some(accounts.charlie));
+
+      assert_eq!(space.referred_by(accounts.bob), Some(accounts.alice));
+      assert_eq!(space.referral_stats(accounts.alice).ok(), Some((1, vec![accounts.bob])));
+      assert_eq!(space.referral_stats(accounts.charlie).ok(), Some((0, Vec::new())));
+    }
+
+    #[ink::test]
+    fn referral_bonus_is_claimed_once() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      space.set_referral_bonus_per_member(100).unwrap();
+      space.record_referral(accounts.bob, Some(accounts.alice));
+      space.record_referral(accounts.charlie, Some(accounts.alice));
+
+      let alice_balance = test::get_account_balance::<DefaultEnvironment>(accounts.alice).unwrap_or_default();
+      assert!(space.claim_referral_bonus().is_ok());
+      assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.alice), Ok(alice_balance + 200));
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE - 200);
+
+      let result = space.claim_referral_bonus();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "No referral bonus to claim"));
+
+      // only new referrals are paid
+      space.record_referral(accounts.django, Some(accounts.alice));
+      assert!(space.claim_referral_bonus().is_ok());
+      assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.alice), Ok(alice_balance + 300));
+    }
+
+    #[ink::test]
+    fn referral_bonus_is_paid_from_treasury_only() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      space.set_referral_bonus_per_member(400).unwrap();
+      space.record_referral(accounts.bob, Some(accounts.alice));
+      space.record_referral(accounts.charlie, Some(accounts.alice));
+
+      // payments of pending requests are not part of the treasury
+      space.escrowed_request_payments.set(&300);
+      let result = space.claim_referral_bonus();
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Insufficient treasury balance"));
+
+      space.release_request_payment(300);
+      assert!(space.claim_referral_bonus().is_ok());
+      assert_eq!(space.treasury_balance(), TREASURY_BALANCE - 800);
+    }
+
+    #[ink::test]
+    fn duplicate_plugin_ids_cannot_be_attached() {
+      let space = new_space(None);
+      let plugins = vec![(*b"POST", AccountId::from([0x01; 32])), (*b"POLL", AccountId::from([0x02; 32])), (*b"POST", AccountId::from([0x03; 32]))];
+
+      let result = space.ensure_attachable_plugins(&plugins);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Duplicate plugin IDs in input"));
+    }
+
+    #[ink::test]
+    fn zero_plugin_id_cannot_be_attached() {
+      let space = new_space(None);
+      let plugins = vec![(*b"POST", AccountId::from([0x01; 32])), ([0u8; 4], AccountId::from([0x02; 32]))];
+
+      let result = space.ensure_attachable_plugins(&plugins);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Invalid plugin id"));
+    }
+
+    #[ink::test]
+    fn installed_plugins_cannot_be_attached_again() {
+      let mut space = new_space(None);
+      space.plugins.insert(*b"POST", &AccountId::from([0x01; 32]));
+      space.plugin_ids.set(&vec![*b"POST"]);
+
+      let plugins = vec![(*b"POLL", AccountId::from([0x02; 32])), (*b"POST", AccountId::from([0x03; 32]))];
+      assert!(matches!(space.ensure_attachable_plugins(&plugins), Err(SpaceError::PluginAlreadyInstalled)));
+      assert!(space.ensure_attachable_plugins(&[(*b"POLL", AccountId::from([0x02; 32]))]).is_ok());
+    }
+
+    /// Space with bob & charlie as active members, bob is the caller
+    fn space_with_members() -> Space {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      space.do_grant_membership(accounts.bob, None, false).unwrap();
+      space.do_grant_membership(accounts.charlie, None, false).unwrap();
+      test::set_caller::<DefaultEnvironment>(accounts.bob);
+
+      space
+    }
+
+    #[ink::test]
+    fn members_can_follow_each_other() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_members();
+
+      space.follow_member(accounts.charlie).unwrap();
+      test::set_caller::<DefaultEnvironment>(accounts.charlie);
+      space.follow_member(accounts.bob).unwrap();
+
+      assert!(space.is_following(accounts.bob, accounts.charlie));
+      assert!(space.is_following(accounts.charlie, accounts.bob));
+      for who in [accounts.bob, accounts.charlie] {
+        assert_eq!(space.follower_count(who), 1);
+        assert_eq!(space.following_count(who), 1);
+      }
+
+      let following: Vec<AccountId> = space.following_list(accounts.bob, 0, 10).items.iter().map(|record| record.account_id).collect();
+      assert_eq!(following, vec![accounts.charlie]);
+
+      let result = space.follow_member(accounts.bob);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Already following this member"));
+    }
+
+    #[ink::test]
+    fn cannot_follow_yourself() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_members();
+
+      let result = space.follow_member(accounts.bob);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Cannot follow yourself"));
+      assert_eq!(space.following_count(accounts.bob), 0);
+    }
+
+    #[ink::test]
+    fn unfollow_decreases_counts() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_members();
+      space.follow_member(accounts.charlie).unwrap();
+
+      space.unfollow_member(accounts.charlie).unwrap();
+      assert!(!space.is_following(accounts.bob, accounts.charlie));
+      assert_eq!(space.follower_count(accounts.charlie), 0);
+      assert_eq!(space.following_count(accounts.bob), 0);
+      assert!(space.following_list(accounts.bob, 0, 10).items.is_empty());
+
+      let result = space.unfollow_member(accounts.charlie);
+      assert!(matches!(result, Err(SpaceError::Custom(message)) if message == "Not following this member"));
+    }
+
+    #[ink::test]
+    fn cannot_follow_non_member() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_members();
+
+      assert!(matches!(space.follow_member(accounts.django), Err(SpaceError::MemberNotFound)));
+
+      // non members cannot follow either
+      test::set_caller::<DefaultEnvironment>(accounts.django);
+      assert!(matches!(space.follow_member(accounts.bob), Err(SpaceError::NotActiveMember)));
+      assert_eq!(space.follower_count(accounts.bob), 0);
+    }
+
+    /// Grant membership to accounts made of `seeds`, each joining 1ms after the previous one
+    fn join_members(space: &mut Space, seeds: core::ops::Range<u8>) -> Vec<(AccountId, Timestamp)> {
+      seeds.map(|seed| {
+        let who = AccountId::from([seed; 32]);
+        let joined_at = seed as Timestamp;
+        test::set_block_timestamp::<DefaultEnvironment>(joined_at);
+        space.do_grant_membership(who, None, false).unwrap();
+
+        (who, joined_at)
+      }).collect()
+    }
+
+    #[ink::test]
+    fn recent_joins_keep_insertion_order() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      // the owner joins on space creation
+      let owner_join = space.newest_member().expect("Owner is not a member");
+      assert_eq!(owner_join.0, accounts.alice);
+      assert_eq!(space.recently_joined_members(10), vec![owner_join]);
+
+      let joins = join_members(&mut space, 0x10..0x13);
+      assert_eq!(space.recently_joined_members(10), vec![joins[2], joins[1], joins[0], owner_join]);
+      assert_eq!(space.recently_joined_members(2), vec![joins[2], joins[1]]);
+      assert_eq!(space.newest_member(), Some(joins[2]));
+    }
+
+    #[ink::test]
+    fn recent_joins_drop_oldest_entries() {
+      let mut space = new_space(None);
+      let joins = join_members(&mut space, 0x10..0x79);
+
+      let recent_joins = space.recently_joined_members(200);
+      assert_eq!(recent_joins.len(), MAX_RECENT_JOINS);
+      assert_eq!(recent_joins.first(), joins.last());
+      // the owner & first 5 joins are dropped
+      assert_eq!(recent_joins.last(), Some(&joins[5]));
+    }
+
+    #[ink::test]
+    fn recent_joins_are_refilled() {
+      let mut space = new_space(None);
+      join_members(&mut space, 0x10..0x74);
+      let joins = join_members(&mut space, 0x74..0xD8);
+
+      let mut recent_joins = space.recently_joined_members(100);
+      recent_joins.reverse();
+      assert_eq!(recent_joins, joins);
+      assert_eq!(space.newest_member(), joins.last().copied());
+    }
+
+    fn space_with_rejoin_cooldown(rejoin_cooldown_secs: Option<u64>) -> Space {
+      new_space(Some(SpaceConfig { rejoin_cooldown_secs, ..Default::default() }))
+    }
+
+    #[ink::test]
+    fn immediate_rejoin_fails_during_cooldown() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_rejoin_cooldown(Some(100));
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      space.do_grant_membership(accounts.bob, None, false).unwrap();
+      mark_left(&mut space, accounts.bob);
+
+      assert_eq!(space.rejoin_available_at(accounts.bob), Some(1_100));
+      let result = space.do_grant_membership(accounts.bob, None, false);
+      assert!(matches!(result, Err(SpaceError::CooldownActive { retry_after: 1_100 })));
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_099);
+      assert!(space.do_grant_membership(accounts.bob, None, false).is_err());
+      assert_eq!(space.member_status(accounts.bob), MemberStatus::Left);
+    }
+
+    #[ink::test]
+    fn rejoin_works_after_cooldown() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_rejoin_cooldown(Some(100));
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      space.do_grant_membership(accounts.bob, None, false).unwrap();
+      mark_left(&mut space, accounts.bob);
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_100);
+      assert!(space.do_grant_membership(accounts.bob, None, false).is_ok());
+      assert_eq!(space.member_status(accounts.bob), MemberStatus::Active);
+    }
+
+    #[ink::test]
+    fn members_who_never_left_are_not_affected_by_cooldown() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_rejoin_cooldown(Some(100));
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+
+      assert_eq!(space.rejoin_available_at(accounts.bob), None);
+      assert!(space.do_grant_membership(accounts.bob, Some(10), false).is_ok());
+
+      // expired members renew right away
+      test::set_block_timestamp::<DefaultEnvironment>(1_020);
+      assert_eq!(space.member_status(accounts.bob), MemberStatus::Inactive);
+      assert!(space.do_grant_membership(accounts.bob, None, false).is_ok());
+    }
+
+    #[ink::test]
+    fn no_cooldown_allows_immediate_rejoin() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = space_with_rejoin_cooldown(None);
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      space.do_grant_membership(accounts.bob, None, false).unwrap();
+      mark_left(&mut space, accounts.bob);
+
+      assert_eq!(space.rejoin_available_at(accounts.bob), None);
+      assert!(space.do_grant_membership(accounts.bob, None, false).is_ok());
+      assert_eq!(space.member_status(accounts.bob), MemberStatus::Active);
+    }
+
+    #[ink::test]
+    fn welcome_message_is_returned_to_new_members() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      let message = String::from("Welcome to InSpace!");
+      space.set_welcome_message(Some(message.clone())).unwrap();
+
+      assert_eq!(space.do_grant_membership(accounts.bob, None, false).unwrap(), Some(message));
+    }
+
+    #[ink::test]
+    fn welcome_message_is_not_returned_on_reactivation() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+      space.set_welcome_message(Some(String::from("Welcome to InSpace!"))).unwrap();
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_000);
+      assert!(space.do_grant_membership(accounts.bob, Some(100), false).unwrap().is_some());
+
+      test::set_block_timestamp::<DefaultEnvironment>(1_200);
+      assert_eq!(space.member_status(accounts.bob), MemberStatus::Inactive);
+      assert_eq!(space.do_grant_membership(accounts.bob, Some(100), false).unwrap(), None);
+      assert_eq!(space.member_status(accounts.bob), MemberStatus::Active);
+    }
+
+    #[ink::test]
+    fn welcome_message_is_none_when_unset() {
+      let accounts = test::default_accounts::<DefaultEnvironment>();
+      let mut space = new_space(None);
+
+      assert_eq!(space.welcome_message(), None);
+      assert_eq!(space.do_grant_membership(accounts.bob, None, false).unwrap(), None);
+
+      space.set_welcome_message(Some(String::from("Welcome to InSpace!"))).unwrap();
+      space.set_welcome_message(None).unwrap();
+      assert_eq!(space.do_grant_membership(accounts.charlie, None, false).unwrap(), None);
+    }
+
+    #[ink::test]
+    fn welcome_message_is_at_max_500_chars() {
+      let mut space = new_space(None);
+
+      assert!(space.set_welcome_message(Some("a".repeat(500))).is_ok());
+      assert!(matches!(space.set_welcome_message(Some("a".repeat(501))), Err(SpaceError::Custom(_))));
+    }
+  }
 }
@@ -1 +1,2 @@
-pub mod macros;
\ No newline at end of file
+pub mod macros;
+pub mod validation;
@@ -0,0 +1,60 @@
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz234567";
+
+/// Check if a string looks like a valid IPFS CID
+/// CIDv0: base58btc encoded, 46 chars starting with `Qm`
+/// CIDv1: base32 encoded, at least 59 chars starting with `bafy` or `bafk`
+pub fn validate_ipfs_cid(cid: &str) -> bool {
+  if !(10..=200).contains(&cid.len()) {
+    return false;
+  }
+
+  if cid.starts_with("Qm") {
+    return cid.len() == 46 && cid.chars().all(|c| BASE58_ALPHABET.contains(c));
+  }
+
+  if cid.starts_with("bafy") || cid.starts_with("bafk") {
+    // skip the multibase prefix `b`
+    return cid.len() >= 59 && cid.chars().skip(1).all(|c| BASE32_ALPHABET.contains(c));
+  }
+
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CID_V0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+  const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+  #[test]
+  fn valid_cid_v0() {
+    assert!(validate_ipfs_cid(CID_V0));
+  }
+
+  #[test]
+  fn valid_cid_v1() {
+    assert!(validate_ipfs_cid(CID_V1));
+    assert!(validate_ipfs_cid(&CID_V1.replacen("bafy", "bafk", 1)));
+  }
+
+  #[test]
+  fn truncated_cid() {
+    assert!(!validate_ipfs_cid(&CID_V0[..45]));
+    assert!(!validate_ipfs_cid(&CID_V1[..58]));
+    assert!(!validate_ipfs_cid("Qm"));
+  }
+
+  #[test]
+  fn wrong_charset_cid() {
+    // `0` & `l` are not in the base58 alphabet
+    assert!(!validate_ipfs_cid(&CID_V0.replacen('Y', "0", 1)));
+    assert!(!validate_ipfs_cid(&CID_V0.replacen('Y', "l", 1)));
+    // base32 is lowercase & has no `1`
+    assert!(!validate_ipfs_cid(&CID_V1.to_uppercase()));
+    assert!(!validate_ipfs_cid(&CID_V1.replacen('5', "1", 1)));
+    // neither v0 nor v1 prefix
+    assert!(!validate_ipfs_cid(&CID_V0.replacen("Qm", "Xm", 1)));
+  }
+}
@@ -0,0 +1,29 @@
+use openbrush::traits::Storage;
+pub use crate::traits::migratable;
+
+pub type StorageVersion = u32;
+
+#[derive(Default, Debug)]
+#[openbrush::storage_item]
+pub struct Data {
+  #[lazy]
+  pub storage_version: StorageVersion,
+}
+
+/// Track storage version of an upgradeable contract,
+/// contracts run their own migration steps and bump the version via `_set_storage_version`
+#[openbrush::trait_definition]
+pub trait Migratable: Storage<Data> {
+  #[ink(message)]
+  fn storage_version(&self) -> StorageVersion {
+    self._storage_version()
+  }
+
+  fn _storage_version(&self) -> StorageVersion {
+    self.data().storage_version.get_or_default()
+  }
+
+  fn _set_storage_version(&mut self, version: StorageVersion) {
+    self.data().storage_version.set(&version);
+  }
+}
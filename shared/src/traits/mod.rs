@@ -1,4 +1,6 @@
 pub mod codehash;
+pub mod migratable;
+pub mod motherspace;
 pub mod plugin_launcher;
 pub mod space_profile;
 pub mod plugin_base;
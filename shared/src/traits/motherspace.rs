@@ -0,0 +1,26 @@
+use ink::prelude::string::String;
+use openbrush::contracts::ownable::OwnableError;
+
+pub type MotherSpaceResult<T> = core::result::Result<T, MotherSpaceError>;
+
+/// Errors of MotherSpace, spaces use this to decode replies of their calls to MotherSpace
+#[derive(Debug, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum MotherSpaceError {
+  Custom(String),
+  OwnableError(OwnableError),
+  UnAuthorized,
+  SpaceNotFound,
+  PluginNotFound,
+  PluginLaunchFailed,
+  PluginIdExisted,
+  SpaceCallFailed,
+  InsufficientBalance,
+  WithdrawFailed,
+}
+
+impl From<OwnableError> for MotherSpaceError {
+  fn from(error: OwnableError) -> Self {
+    MotherSpaceError::OwnableError(error)
+  }
+}
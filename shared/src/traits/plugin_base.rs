@@ -32,6 +32,10 @@ pub struct Data {
 
   #[lazy]
   pub launcher_id: AccountId,
+
+  /// Skip checking new code hash against launcher's known plugin codes, for emergency only
+  #[lazy]
+  pub allow_arbitrary_code: bool,
 }
 
 #[openbrush::trait_definition]
@@ -49,11 +53,28 @@ pub trait PluginBase: Storage<Data> {
   #[ink(message)]
   #[modifiers(only_space_owner)]
   fn set_code_hash(&mut self, new_code_hash: Hash) -> PluginResult<()> {
+    if !self.allow_arbitrary_code() && !self._is_known_plugin_code(new_code_hash) {
+      return Err(PluginError::Custom(String::from("Code hash not recognized by launcher")));
+    }
+
     Self::env()
       .set_code_hash(&new_code_hash)
       .map_err(|_| PluginError::SetCodeHashFailed)
   }
 
+  #[ink(message)]
+  fn allow_arbitrary_code(&self) -> bool {
+    self.data().allow_arbitrary_code.get_or_default()
+  }
+
+  #[ink(message)]
+  #[modifiers(only_space_owner)]
+  fn set_allow_arbitrary_code(&mut self, allowed: bool) -> PluginResult<()> {
+    self.data().allow_arbitrary_code.set(&allowed);
+
+    Ok(())
+  }
+
   fn _space_id(&self) -> AccountId {
     self.data().space_id.get().unwrap()
   }
@@ -62,6 +83,45 @@ pub trait PluginBase: Storage<Data> {
     self.data().launcher_id.get().unwrap()
   }
 
+  /// Check if a code hash is the latest or an older version of the plugin code in the launcher
+  fn _is_known_plugin_code(&self, code_hash: Hash) -> bool {
+    let latest_code = build_call::<DefaultEnvironment>()
+      .call(self._launcher_id())
+      .gas_limit(0)
+      .exec_input(
+        ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::latest_plugin_code")))
+      )
+      .returns::<Hash>()
+      .invoke();
+
+    if latest_code == code_hash {
+      return true;
+    }
+
+    let latest_version = build_call::<DefaultEnvironment>()
+      .call(self._launcher_id())
+      .gas_limit(0)
+      .exec_input(
+        ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::latest_plugin_version")))
+      )
+      .returns::<u32>()
+      .invoke();
+
+    (1..latest_version).any(|version| {
+      let maybe_code = build_call::<DefaultEnvironment>()
+        .call(self._launcher_id())
+        .gas_limit(0)
+        .exec_input(
+          ExecutionInput::new(Selector::new(ink::selector_bytes!("PluginLauncher::plugin_code_at_version")))
+            .push_arg(version)
+        )
+        .returns::<Option<Hash>>()
+        .invoke();
+
+      maybe_code == Some(code_hash)
+    })
+  }
+
   fn _ensure_active_member(&self) -> PluginResult<()> {
     let caller = Self::env().caller();
 
@@ -94,6 +154,34 @@ pub trait PluginBase: Storage<Data> {
     }
   }
 
+  /// Read a feature flag of this plugin configured by the space owner, `default` if the flag is not set
+  fn _feature_flag(&self, flag: &str, default: bool) -> bool {
+    let maybe_plugin_id = build_call::<DefaultEnvironment>()
+      .call(self._space_id())
+      .gas_limit(0)
+      .exec_input(
+        ExecutionInput::new(Selector::new(ink::selector_bytes!("plugin_id_of")))
+          .push_arg(Self::env().account_id())
+      )
+      .returns::<Option<[u8; 4]>>()
+      .invoke();
+
+    let Some(plugin_id) = maybe_plugin_id else { return default };
+
+    let maybe_enabled = build_call::<DefaultEnvironment>()
+      .call(self._space_id())
+      .gas_limit(0)
+      .exec_input(
+        ExecutionInput::new(Selector::new(ink::selector_bytes!("plugin_feature_flag")))
+          .push_arg(plugin_id)
+          .push_arg(String::from(flag))
+      )
+      .returns::<Option<bool>>()
+      .invoke();
+
+    maybe_enabled.unwrap_or(default)
+  }
+
   fn _init(&mut self, space_id: AccountId, launcher_id: AccountId) {
     self.data().space_id.set(&space_id);
     self.data().launcher_id.set(&launcher_id);
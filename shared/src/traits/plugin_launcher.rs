@@ -1,4 +1,4 @@
-use ink::prelude::string::String;
+use ink::prelude::{string::String, vec::Vec};
 use openbrush::{
   traits::{
     AccountId,
@@ -40,6 +40,10 @@ pub struct Data {
 
   #[lazy]
   pub launches_count: u32,
+
+  pub space_installs: Mapping<AccountId, u32>,
+  #[lazy]
+  pub distinct_spaces: u32,
 }
 
 #[openbrush::trait_definition]
@@ -49,6 +53,16 @@ pub trait PluginLauncher: Storage<Data> + Instantiator {
     self._latest_plugin_code()
   }
 
+  #[ink(message)]
+  fn latest_plugin_version(&self) -> Version {
+    self.data().plugin_codes_nonce.get_or_default()
+  }
+
+  #[ink(message)]
+  fn plugin_code_at_version(&self, version: Version) -> Option<Hash> {
+    self.data().plugin_codes.get(&version)
+  }
+
   #[ink(message)]
   fn upgrade_plugin_code(&mut self, new_code_hash: Hash) -> Result<Version, LauncherError> {
     // For now, we can only upgrade plugin code via motherspace
@@ -61,6 +75,18 @@ pub trait PluginLauncher: Storage<Data> + Instantiator {
     self.data().launches_count.get_or_default()
   }
 
+  /// Number of plugins launched for a space
+  #[ink(message)]
+  fn installs_for_space(&self, space_id: AccountId) -> u32 {
+    self.data().space_installs.get(&space_id).unwrap_or_default()
+  }
+
+  /// Number of distinct spaces having launched the plugin
+  #[ink(message)]
+  fn spaces_using_plugin_count(&self) -> u32 {
+    self.data().distinct_spaces.get_or_default()
+  }
+
   #[ink(message)]
   fn motherspace_id(&self) -> AccountId {
     self.data().motherspace_id.get().unwrap()
@@ -68,6 +94,12 @@ pub trait PluginLauncher: Storage<Data> + Instantiator {
 
   #[ink(message)]
   fn launch(&mut self, space_id: AccountId) -> Result<AccountId, LauncherError> {
+    self.launch_with_config(space_id, Vec::new())
+  }
+
+  /// Launch a new plugin with initialization data passed to the plugin constructor
+  #[ink(message)]
+  fn launch_with_config(&mut self, space_id: AccountId, init_data: Vec<u8>) -> Result<AccountId, LauncherError> {
     let launcher_id = Self::env().account_id();
 
     let next_launches_count =
@@ -76,10 +108,17 @@ pub trait PluginLauncher: Storage<Data> + Instantiator {
         .expect("Exceeds number of launch count!");
 
     let salt = next_launches_count.to_le_bytes();
-    let new_contract_id = self._initiate_new_plugin(space_id, launcher_id, &salt)?;
+    let new_contract_id = self._initiate_new_plugin(space_id, launcher_id, &salt, &init_data)?;
 
     self.data().launches_count.set(&next_launches_count);
 
+    let space_installs = self.installs_for_space(space_id);
+    if space_installs == 0 {
+      let distinct_spaces = self.data().distinct_spaces.get_or_default().saturating_add(1);
+      self.data().distinct_spaces.set(&distinct_spaces);
+    }
+    self.data().space_installs.insert(&space_id, &space_installs.saturating_add(1));
+
     Ok(new_contract_id)
   }
 
@@ -108,7 +147,7 @@ pub trait PluginLauncher: Storage<Data> + Instantiator {
 
 pub trait Instantiator {
   /// Internal function which instantiates a shares contract and returns its AccountId
-  fn _initiate_new_plugin(&self, _space_id: AccountId, _launcher_id: AccountId, _salt: &[u8]) -> Result<AccountId, LauncherError> {
+  fn _initiate_new_plugin(&self, _space_id: AccountId, _launcher_id: AccountId, _salt: &[u8], _init_data: &[u8]) -> Result<AccountId, LauncherError> {
     Err(LauncherError::Custom(String::from("TODD implement: _initiate_new_plugin")))
   }
 }
\ No newline at end of file
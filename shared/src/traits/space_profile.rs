@@ -1,4 +1,6 @@
 use ink::prelude::string::String;
+use ink::primitives::Key;
+use ink::storage::traits::StorageKey;
 use openbrush::{
   modifiers,
   traits::{
@@ -9,6 +11,7 @@ use openbrush::{
   contracts::{ownable::*}
 };
 use crate::ensure;
+use crate::helpers::validation::validate_ipfs_cid;
 pub use crate::traits::space_profile;
 
 #[derive(Debug, scale::Decode, scale::Encode)]
@@ -23,6 +26,13 @@ pub enum SpaceError {
   NotActiveMember,
   MemberNotFound,
   PluginNotFound,
+  CooldownActive { retry_after: u64 },
+  // Since version 0.1.0
+  OwnerCannotLeave,
+  // Since version 0.1.0
+  PluginAlreadyInstalled,
+  // Since version 0.1.0
+  PendingRequestsAtCapacity,
 }
 
 impl From<OwnableError> for SpaceError {
@@ -38,6 +48,16 @@ pub enum ImageSource {
   Url(String),
 }
 
+impl ImageSource {
+  pub fn validate(&self) -> Result<(), SpaceError> {
+    if let ImageSource::IpfsCid(cid) = self {
+      ensure!(validate_ipfs_cid(cid), SpaceError::Custom(String::from("Invalid IPFS CID")));
+    }
+
+    Ok(())
+  }
+}
+
 #[derive(Debug, Default, scale::Decode, scale::Encode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub struct SpaceInfo {
@@ -62,7 +82,18 @@ pub enum Pricing {
   #[default]
   Free,
   OneTimePaid { price: Balance },
-  Subscription { price: Balance, duration: u32 }, // duration is in days
+  Subscription { price: Balance, duration_secs: u64 },
+  /// Subscription paid with a PSP22 token instead of native currency
+  SubscriptionWithToken { token_contract: AccountId, price: Balance, duration_secs: u64 },
+}
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+impl Pricing {
+  /// Subscription pricing with duration in days
+  pub fn subscription_days(price: Balance, days: u32) -> Pricing {
+    Pricing::Subscription { price, duration_secs: SECS_PER_DAY.saturating_mul(days as u64) }
+  }
 }
 
 #[derive(Debug, Default, scale::Decode, scale::Encode)]
@@ -70,22 +101,67 @@ pub enum Pricing {
 pub struct SpaceConfig {
   pub registration: RegistrationType,
   pub pricing: Pricing,
+  /// Waiting time before a member who left can join again
+  pub rejoin_cooldown_secs: Option<u64>,
+  /// Who can pay to join on behalf of a registrant:
+  /// false (default) -> only the payer authorized by the registrant with `authorize_payer`,
+  /// true -> anyone, without the registrant's authorization
+  pub allow_third_party_payment: bool,
+  /// Lower the maximum number of pending membership requests, the space's hard limit applies if None
+  pub max_pending_requests: Option<u64>,
 }
 
-const SECS_PER_DAY: u64 = 24 * 60 * 60;
-
 impl SpaceConfig {
   /// Calculate time to live (ttl) for a membership
   /// None -> Non expiring
   /// Some -> Expiring in seconds from the approved time
   pub fn ttl(&self) -> Option<u64> {
     match self.pricing {
-      Pricing::Subscription { duration, .. } => Some(SECS_PER_DAY.saturating_mul(duration as u64)),
+      Pricing::Subscription { duration_secs, .. } => Some(duration_secs),
+      Pricing::SubscriptionWithToken { duration_secs, .. } => Some(duration_secs),
       _ => None,
     }
   }
 }
 
+/// Pricing layout before subscription duration was stored in seconds, only used for migration
+#[derive(scale::Decode)]
+enum LegacyPricing {
+  Free,
+  OneTimePaid { price: Balance },
+  Subscription { price: Balance, duration: u32 }, // duration is in days
+}
+
+#[derive(scale::Decode)]
+struct LegacySpaceConfig {
+  registration: RegistrationType,
+  pricing: LegacyPricing,
+}
+
+/// Config layout before rejoin cooldown was added, only used for migration
+#[derive(scale::Encode, scale::Decode)]
+struct SpaceConfigV1 {
+  registration: RegistrationType,
+  pricing: Pricing,
+}
+
+/// Config layout before third party payment setting was added, only used for migration
+#[derive(scale::Encode, scale::Decode)]
+struct SpaceConfigV2 {
+  registration: RegistrationType,
+  pricing: Pricing,
+  rejoin_cooldown_secs: Option<u64>,
+}
+
+/// Config layout before pending requests capacity override was added, only used for migration
+#[derive(scale::Encode, scale::Decode)]
+struct SpaceConfigV3 {
+  registration: RegistrationType,
+  pricing: Pricing,
+  rejoin_cooldown_secs: Option<u64>,
+  allow_third_party_payment: bool,
+}
+
 #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum SpaaceInfoError {
@@ -121,7 +197,12 @@ pub trait SpaceProfile: Storage<Data> + Storage<ownable::Data> {
   #[modifiers(only_owner)]
   fn update_info(&mut self, info: SpaceInfo) -> Result<(), SpaceError> {
     // TODO validate to limit maximum of chars for each field
+    if let Some(logo) = &info.logo {
+      logo.validate()?;
+    }
+
     self.data::<Data>().info.set(&info);
+    self._after_info_updated(&info);
 
     Ok(())
   }
@@ -134,19 +215,31 @@ pub trait SpaceProfile: Storage<Data> + Storage<ownable::Data> {
   #[ink(message)]
   #[modifiers(only_owner)]
   fn update_config(&mut self, config: SpaceConfig) -> Result<(), SpaceError> {
-    self.data::<Data>().config.set(&Self::_normalize_config(Some(config)));
+    self._ensure_not_paused()?;
+    self.data::<Data>().config.set(&Self::_normalize_config(Some(config))?);
 
     Ok(())
   }
 
+  /// Hook called after space info is updated
+  fn _after_info_updated(&mut self, _info: &SpaceInfo) {}
+
+  /// Hook to block writes while the space is paused
+  fn _ensure_not_paused(&self) -> Result<(), SpaceError> {
+    Ok(())
+  }
+
   fn _default_config() -> SpaceConfig {
     SpaceConfig {
       registration: RegistrationType::PayToJoin,
       pricing: Pricing::Free,
+      rejoin_cooldown_secs: None,
+      allow_third_party_payment: false,
+      max_pending_requests: None,
     }
   }
 
-  fn _normalize_config(maybe_config: Option<SpaceConfig>) -> SpaceConfig {
+  fn _normalize_config(maybe_config: Option<SpaceConfig>) -> Result<SpaceConfig, SpaceError> {
     match maybe_config {
       Some(mut one) => {
         // Invite only mode only accept free pricing
@@ -155,9 +248,84 @@ pub trait SpaceProfile: Storage<Data> + Storage<ownable::Data> {
           one.pricing = Pricing::Free;
         }
 
-        one
+        if let Pricing::Subscription { duration_secs, .. } | Pricing::SubscriptionWithToken { duration_secs, .. } = one.pricing {
+          ensure!(duration_secs > 0, SpaceError::Custom(String::from("Subscription duration must be greater than 0")));
+        }
+
+        if let Some(max_pending_requests) = one.max_pending_requests {
+          ensure!(max_pending_requests > 0, SpaceError::Custom(String::from("Maximum of pending requests must be greater than 0")));
+        }
+
+        Ok(one)
       }
-      None => Self::_default_config()
+      None => Ok(Self::_default_config())
+    }
+  }
+
+  /// Convert stored config from subscription duration in days to seconds
+  fn _migrate_subscription_duration_to_secs(&mut self) {
+    let key: Key = StorageKey::key(&self.data::<Data>().config);
+    let maybe_legacy = ink::env::get_contract_storage::<Key, LegacySpaceConfig>(&key);
+
+    if let Ok(Some(legacy)) = maybe_legacy {
+      let pricing = match legacy.pricing {
+        LegacyPricing::Free => Pricing::Free,
+        LegacyPricing::OneTimePaid { price } => Pricing::OneTimePaid { price },
+        LegacyPricing::Subscription { price, duration } => Pricing::subscription_days(price, duration),
+      };
+
+      // written in the v1 layout so the next migration step can pick it up
+      ink::env::set_contract_storage(&key, &SpaceConfigV1 {
+        registration: legacy.registration,
+        pricing,
+      });
+    }
+  }
+
+  /// Add rejoin cooldown to stored config, no cooldown by default
+  fn _migrate_add_rejoin_cooldown(&mut self) {
+    let key: Key = StorageKey::key(&self.data::<Data>().config);
+    let maybe_v1 = ink::env::get_contract_storage::<Key, SpaceConfigV1>(&key);
+
+    if let Ok(Some(v1)) = maybe_v1 {
+      // written in the v2 layout so the next migration step can pick it up
+      ink::env::set_contract_storage(&key, &SpaceConfigV2 {
+        registration: v1.registration,
+        pricing: v1.pricing,
+        rejoin_cooldown_secs: None,
+      });
+    }
+  }
+
+  /// Add third party payment setting to stored config, disabled by default
+  fn _migrate_add_third_party_payment(&mut self) {
+    let key: Key = StorageKey::key(&self.data::<Data>().config);
+    let maybe_v2 = ink::env::get_contract_storage::<Key, SpaceConfigV2>(&key);
+
+    if let Ok(Some(v2)) = maybe_v2 {
+      // written in the v3 layout so the next migration step can pick it up
+      ink::env::set_contract_storage(&key, &SpaceConfigV3 {
+        registration: v2.registration,
+        pricing: v2.pricing,
+        rejoin_cooldown_secs: v2.rejoin_cooldown_secs,
+        allow_third_party_payment: false,
+      });
+    }
+  }
+
+  /// Add pending requests capacity override to stored config, no override by default
+  fn _migrate_add_max_pending_requests(&mut self) {
+    let key: Key = StorageKey::key(&self.data::<Data>().config);
+    let maybe_v3 = ink::env::get_contract_storage::<Key, SpaceConfigV3>(&key);
+
+    if let Ok(Some(v3)) = maybe_v3 {
+      self.data::<Data>().config.set(&SpaceConfig {
+        registration: v3.registration,
+        pricing: v3.pricing,
+        rejoin_cooldown_secs: v3.rejoin_cooldown_secs,
+        allow_third_party_payment: v3.allow_third_party_payment,
+        max_pending_requests: None,
+      });
     }
   }
 
@@ -169,8 +337,12 @@ pub trait SpaceProfile: Storage<Data> + Storage<ownable::Data> {
       ensure!(desc.len() <= 200, SpaceError::Custom(String::from("Space description is at max 100 chars")));
     }
 
+    if let Some(logo) = &space_info.logo {
+      logo.validate()?;
+    }
+
     self.data::<Data>().info.set(&space_info);
-    self.data::<Data>().config.set(&Self::_normalize_config(config));
+    self.data::<Data>().config.set(&Self::_normalize_config(config)?);
 
     Ok(())
   }